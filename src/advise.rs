@@ -0,0 +1,426 @@
+use crate::alias::AliasMap;
+use crate::cache::Cache;
+use crate::config::Config;
+use crate::cost::TokenMix;
+use crate::models::SourceResult;
+use crate::output::OutputFormat;
+use crate::recommend::{
+    RecommendMetricValue, SourceMetricSpec, TaskRow, TaskSpec, best_rows, compare_metric,
+    fetch_recommend_sources, format_metric, openrouter_listings, spec_for_task, status_label,
+    warn_missing_metrics,
+};
+use crate::variant::VariantPolicy;
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+
+/// Exit status of `advise --exit-code` when a rostered model has an upgrade.
+/// Errors exit with 1, so a scheduled job can tell the two apart.
+pub const UPGRADE_EXIT_STATUS: i32 = 2;
+
+/// One task metric compared between a rostered model and an upgrade.
+#[derive(Debug, Serialize)]
+pub struct Evidence {
+    pub source: String,
+    pub label: String,
+    pub metric: String,
+    /// Source row read for the rostered model
+    pub current_row: String,
+    pub current: RecommendMetricValue,
+    pub candidate_row: String,
+    pub candidate: RecommendMetricValue,
+    /// Whether the candidate is strictly better here, rather than level
+    pub better: bool,
+}
+
+/// A model priced at or below the rostered one that is listed by every task
+/// source listing the rostered model, no worse in any and better in one.
+#[derive(Debug, Serialize)]
+pub struct Upgrade {
+    pub model: String,
+    pub price_per_1m: f64,
+    pub evidence: Vec<Evidence>,
+}
+
+impl Upgrade {
+    fn wins(&self) -> usize {
+        self.evidence
+            .iter()
+            .filter(|evidence| evidence.better)
+            .count()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RosterAdvice {
+    pub model: String,
+    /// Blended OpenRouter price, 3 input : 1 output
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_per_1m: Option<f64>,
+    /// Task sources that list the model
+    pub sources: Vec<String>,
+    /// Why upgrades couldn't be looked for, if they couldn't
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub upgrades: Vec<Upgrade>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdviseOutput {
+    pub timestamp: DateTime<Utc>,
+    pub task: String,
+    pub roster: Vec<RosterAdvice>,
+    /// Rostered models with at least one upgrade
+    pub upgradable: usize,
+}
+
+impl AdviseOutput {
+    /// 0, or [`UPGRADE_EXIT_STATUS`] when any rostered model has an upgrade.
+    pub fn exit_status(&self) -> i32 {
+        if self.upgradable > 0 {
+            UPGRADE_EXIT_STATUS
+        } else {
+            0
+        }
+    }
+}
+
+/// Fetch the task's sources and OpenRouter, print advice for each rostered
+/// model and return the exit status for `--exit-code`.
+pub fn run(
+    config: &Config,
+    cache: &Cache,
+    aliases: &AliasMap,
+    task: Option<&str>,
+    models: &[String],
+    format: OutputFormat,
+) -> Result<i32> {
+    let roster = if models.is_empty() {
+        &config.roster.models
+    } else {
+        models
+    };
+    if roster.is_empty() {
+        bail!(
+            "No models to advise on. Add `models = [...]` under [roster] in config, or name models on the command line"
+        );
+    }
+
+    let spec = spec_for_task(&config.recommend, task.unwrap_or(&config.roster.task))?;
+    let mut names = spec.source_names();
+    if !names.contains(&"openrouter") {
+        names.push("openrouter");
+    }
+    let results = fetch_recommend_sources(config, cache, &names)?;
+    for result in &results {
+        eprintln!("[{}] {}", result.source, status_label(&result.status));
+    }
+
+    let cost_mix = TokenMix::from_config(&config.cost);
+    warn_missing_metrics(&spec, &results, &cost_mix);
+    let output = advise(&spec, &results, aliases, &cost_mix, roster);
+    println!("{}", render(&spec, &output, format)?);
+    Ok(output.exit_status())
+}
+
+pub fn advise(
+    spec: &TaskSpec,
+    results: &[SourceResult],
+    aliases: &AliasMap,
+    cost_mix: &TokenMix,
+    roster: &[String],
+) -> AdviseOutput {
+    let rows: Vec<(&SourceMetricSpec, HashMap<String, TaskRow>)> = spec
+        .sources
+        .iter()
+        .filter_map(|source_spec| {
+            let result = results
+                .iter()
+                .find(|result| result.source == source_spec.source)?;
+            Some((
+                source_spec,
+                best_rows(source_spec, result, aliases, cost_mix, VariantPolicy::Best),
+            ))
+        })
+        .collect();
+    let prices: HashMap<String, f64> = results
+        .iter()
+        .find(|result| result.source == "openrouter")
+        .map(|result| openrouter_listings(result, aliases))
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(model, listing)| Some((model, listing.price?)))
+        .collect();
+    let candidates: BTreeSet<&str> = rows
+        .iter()
+        .flat_map(|(_, rows)| rows.keys().map(String::as_str))
+        .collect();
+
+    let roster: Vec<RosterAdvice> = roster
+        .iter()
+        .map(|name| {
+            let model = aliases.resolve(name);
+            let mut advice = RosterAdvice {
+                sources: rows
+                    .iter()
+                    .filter(|(_, rows)| rows.contains_key(&model))
+                    .map(|(source_spec, _)| source_spec.source.clone())
+                    .collect(),
+                price_per_1m: prices.get(&model).copied(),
+                model,
+                note: None,
+                upgrades: Vec::new(),
+            };
+            let Some(price) = advice.price_per_1m else {
+                advice.note = Some("no OpenRouter price to compare against".to_string());
+                return advice;
+            };
+            if advice.sources.is_empty() {
+                advice.note = Some(format!("not listed by any {} source", spec.name));
+                return advice;
+            }
+
+            advice.upgrades = candidates
+                .iter()
+                .filter(|candidate| **candidate != advice.model)
+                .filter_map(|candidate| {
+                    let price_per_1m = *prices.get(*candidate)?;
+                    if price_per_1m > price {
+                        return None;
+                    }
+                    Some(Upgrade {
+                        model: candidate.to_string(),
+                        price_per_1m,
+                        evidence: evidence(&rows, &advice.model, candidate)?,
+                    })
+                })
+                .collect();
+            advice.upgrades.sort_by(|left, right| {
+                right
+                    .wins()
+                    .cmp(&left.wins())
+                    .then(left.price_per_1m.total_cmp(&right.price_per_1m))
+                    .then_with(|| left.model.cmp(&right.model))
+            });
+            advice
+        })
+        .collect();
+
+    AdviseOutput {
+        timestamp: Utc::now(),
+        task: spec.name.clone(),
+        upgradable: roster
+            .iter()
+            .filter(|advice| !advice.upgrades.is_empty())
+            .count(),
+        roster,
+    }
+}
+
+/// The case for `candidate` over `current`: a row per source listing
+/// `current`, or `None` if the candidate is missing from or worse in any of
+/// them, or better in none.
+fn evidence(
+    rows: &[(&SourceMetricSpec, HashMap<String, TaskRow>)],
+    current: &str,
+    candidate: &str,
+) -> Option<Vec<Evidence>> {
+    let mut evidence = Vec::new();
+    for (source_spec, rows) in rows {
+        let Some(current_row) = rows.get(current) else {
+            continue;
+        };
+        let candidate_row = rows.get(candidate)?;
+        let ordering = compare_metric(candidate_row.value, current_row.value, source_spec.sort);
+        if ordering == Ordering::Greater {
+            return None;
+        }
+        evidence.push(Evidence {
+            source: source_spec.source.clone(),
+            label: source_spec.label.clone(),
+            metric: source_spec.metric.clone(),
+            current_row: current_row.source_model_name.clone(),
+            current: current_row.value,
+            candidate_row: candidate_row.source_model_name.clone(),
+            candidate: candidate_row.value,
+            better: ordering == Ordering::Less,
+        });
+    }
+    evidence
+        .iter()
+        .any(|evidence| evidence.better)
+        .then_some(evidence)
+}
+
+pub fn render(spec: &TaskSpec, output: &AdviseOutput, format: OutputFormat) -> Result<String> {
+    Ok(match format {
+        OutputFormat::Json => serde_json::to_string_pretty(output)?,
+        OutputFormat::Table => render_text(spec, output, false),
+        OutputFormat::Markdown => render_text(spec, output, true),
+    })
+}
+
+fn render_text(spec: &TaskSpec, output: &AdviseOutput, markdown: bool) -> String {
+    let sources = spec
+        .sources
+        .iter()
+        .map(|source| source.source.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut lines = vec![
+        format!(
+            "Task: {}  (sources: {sources}; prices: blended OpenRouter $/1M)",
+            output.task
+        ),
+        format!(
+            "{} of {} rostered model(s) have an upgrade",
+            output.upgradable,
+            output.roster.len()
+        ),
+    ];
+
+    let (heading, bullet, indent) = if markdown {
+        ("### ", "- ", "  - ")
+    } else {
+        ("", "  → ", "      ")
+    };
+    for advice in &output.roster {
+        lines.push(String::new());
+        let price = advice
+            .price_per_1m
+            .map_or_else(|| "no price".to_string(), |price| format!("${price:.2}"));
+        lines.push(format!("{heading}{} ({price})", advice.model));
+        if let Some(note) = &advice.note {
+            lines.push(format!("{bullet}{note}"));
+            continue;
+        }
+        if advice.upgrades.is_empty() {
+            lines.push(format!(
+                "{bullet}no model at this price or less beats it on {}",
+                advice.sources.join(", ")
+            ));
+        }
+        for upgrade in &advice.upgrades {
+            lines.push(format!(
+                "{bullet}{} at ${:.2}, better on {} of {} source(s)",
+                upgrade.model,
+                upgrade.price_per_1m,
+                upgrade.wins(),
+                upgrade.evidence.len()
+            ));
+            for evidence in &upgrade.evidence {
+                lines.push(format!(
+                    "{indent}{}: {} ({}) vs {} ({})",
+                    evidence.label,
+                    format_metric(&evidence.metric, evidence.candidate),
+                    evidence.candidate_row,
+                    format_metric(&evidence.metric, evidence.current),
+                    evidence.current_row
+                ));
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RecommendConfig;
+    use crate::models::{MetricValue, ModelScore, SourceStatus, Variant};
+
+    fn score(model: &str, metrics: &[(&str, f64)]) -> ModelScore {
+        ModelScore {
+            model: model.to_string(),
+            source_model_name: format!("{model}-row"),
+            variant: Variant::default(),
+            agent: None,
+            metrics: metrics
+                .iter()
+                .map(|(name, value)| (name.to_string(), MetricValue::Float(*value)))
+                .collect(),
+            rank: None,
+        }
+    }
+
+    fn source(name: &str, scores: Vec<ModelScore>) -> SourceResult {
+        SourceResult {
+            source: name.to_string(),
+            fetched_at: None,
+            status: SourceStatus::Cached,
+            scores,
+        }
+    }
+
+    #[test]
+    fn upgrades_must_match_on_every_source_at_no_higher_price() {
+        let aliases = AliasMap::bundled();
+        let spec = spec_for_task(&RecommendConfig::default(), "coding").unwrap();
+        let results = vec![
+            source(
+                "swebench",
+                vec![
+                    score("current", &[("resolved_rate", 70.0)]),
+                    score("better", &[("resolved_rate", 72.0)]),
+                    score("level", &[("resolved_rate", 70.0)]),
+                    score("pricier", &[("resolved_rate", 80.0)]),
+                    score("partial", &[("resolved_rate", 90.0)]),
+                ],
+            ),
+            source(
+                "aider",
+                vec![
+                    score("current", &[("pass_rate_1", 60.0)]),
+                    score("better", &[("pass_rate_1", 60.0)]),
+                    score("level", &[("pass_rate_1", 60.0)]),
+                    score("pricier", &[("pass_rate_1", 65.0)]),
+                ],
+            ),
+            source(
+                "openrouter",
+                ["current", "better", "level", "pricier", "partial"]
+                    .iter()
+                    .zip([4.0, 2.0, 1.0, 8.0, 1.0])
+                    .map(|(model, price)| {
+                        score(
+                            model,
+                            &[("prompt_per_1m", price), ("completion_per_1m", price)],
+                        )
+                    })
+                    .collect(),
+            ),
+        ];
+        let cost_mix = TokenMix::from_config(&Default::default());
+        let roster = ["current".to_string(), "unknown".to_string()];
+        let output = advise(&spec, &results, &aliases, &cost_mix, &roster);
+
+        assert_eq!(output.upgradable, 1);
+        assert_eq!(output.exit_status(), UPGRADE_EXIT_STATUS);
+        let advice = &output.roster[0];
+        assert_eq!(advice.sources, vec!["swebench", "aider"]);
+        let upgrades: Vec<&str> = advice
+            .upgrades
+            .iter()
+            .map(|upgrade| upgrade.model.as_str())
+            .collect();
+        assert_eq!(upgrades, vec!["better"]);
+        let evidence = &advice.upgrades[0].evidence;
+        assert_eq!(evidence.len(), 2);
+        assert!(evidence[0].better && !evidence[1].better);
+        assert_eq!(evidence[0].candidate_row, "better-row");
+        assert_eq!(evidence[0].current_row, "current-row");
+
+        assert_eq!(
+            output.roster[1].note.as_deref(),
+            Some("no OpenRouter price to compare against")
+        );
+
+        // Nothing cheaper beats "pricier" everywhere it's listed
+        let roster = ["pricier".to_string()];
+        let output = advise(&spec, &results, &aliases, &cost_mix, &roster);
+        assert_eq!(output.upgradable, 0);
+        assert_eq!(output.exit_status(), 0);
+    }
+}
@@ -0,0 +1,1428 @@
+use crate::alias::AliasMap;
+use crate::models::{MetricValue, ModelScore, SourceResult, SourceStatus, Variant};
+use crate::ranking::competition_ranks;
+use crate::variant::VariantPolicy;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+/// The metric each source ranks by. All are higher-is-better.
+const PRIMARY_METRICS: &[(&str, &str)] = &[
+    ("artificial-analysis", "intelligence_index"),
+    ("aider", "pass_rate_1"),
+    ("arena", "elo_score"),
+    ("livebench", "global_average"),
+    ("seal", "overall_score"),
+    ("swebench", "resolved_rate"),
+    ("swe-rebench", "resolve_rate"),
+    ("terminal-bench", "tasks_completed"),
+    ("mock", "score"),
+];
+
+/// Metrics that carry a source's own uncertainty about its primary metric, with
+/// the divisor that turns them into a standard error. Arena's `elo_ci` is a 95%
+/// interval half-width; SWE-rebench publishes the SEM itself.
+const UNCERTAINTY_METRICS: &[(&str, &str, f64)] = &[
+    ("arena", "elo_ci", 1.96),
+    ("swe-rebench", "resolve_rate_sem", 1.0),
+];
+
+pub fn primary_metric(source: &str) -> Option<&'static str> {
+    PRIMARY_METRICS
+        .iter()
+        .find(|(name, _)| *name == source)
+        .map(|(_, metric)| *metric)
+}
+
+/// How per-source placements are combined into one score per model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum AggregateMethod {
+    /// Mean rank percentile (rank 1 = 1.0, last = 0.0)
+    #[default]
+    Percentile,
+    /// Mean of per-source z-scores of the raw metric, mapped through the normal CDF
+    Zscore,
+    /// Share of available Borda points: models beaten, summed across sources
+    Borda,
+    /// One minus the geometric mean of normalised mid-ranks
+    RankProduct,
+    /// Mean win probability from a Bradley–Terry fit to pairwise wins
+    BradleyTerry,
+}
+
+impl AggregateMethod {
+    /// Whether the score is a weighted mean of per-source values, so it can be
+    /// split into per-source contributions.
+    fn is_additive(self) -> bool {
+        matches!(self, Self::Percentile | Self::Zscore | Self::Borda)
+    }
+}
+
+/// How a model's score accounts for sources that don't list it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Coverage {
+    /// Average only the sources that list the model
+    #[default]
+    None,
+    /// Pull the score toward the mean of all models in proportion to the
+    /// (weighted) share of sources that don't list the model
+    Shrink,
+    /// Predict each missing source's score from the sources that list the
+    /// model, by linear regression between positively correlated sources
+    Impute,
+}
+
+impl Coverage {
+    fn name(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Shrink => "shrink",
+            Self::Impute => "impute",
+        }
+    }
+}
+
+/// A source's ranked rows after alias resolution: one row per canonical model.
+#[derive(Debug, Clone)]
+pub struct ResolvedSource {
+    pub source: String,
+    /// The primary metric held in each row's `value`, or `None` when negated
+    /// ranks stand in for it.
+    pub metric: Option<&'static str>,
+    /// Sorted by `rank`, which is re-assigned after deduplication.
+    pub rows: Vec<ResolvedRow>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedRow {
+    /// Canonical model name.
+    pub model: String,
+    /// The name the source itself lists the chosen row under.
+    pub source_model_name: String,
+    pub rank: u32,
+    /// The source's primary metric, higher is better. Sources without one (or
+    /// with rows missing it) use the negated original rank instead.
+    pub value: f64,
+    /// Standard error of `value`, when the source publishes one.
+    pub std_error: Option<f64>,
+}
+
+impl ResolvedSource {
+    pub fn total(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// `row`'s percentile within this source, with tied rows sharing the
+    /// percentile of their mid-rank.
+    pub fn percentile_of(&self, row: &ResolvedRow) -> f64 {
+        let values: Vec<f64> = self.rows.iter().map(|row| row.value).collect();
+        percentile(mid_rank(row.value, &values), self.total())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AggregateOptions {
+    pub min_sources: usize,
+    pub show_excluded: bool,
+    pub variant: VariantPolicy,
+    pub method: AggregateMethod,
+    pub coverage: Coverage,
+    /// Per-source weights from a `[aggregate.profiles.<name>]` table. Sources not
+    /// listed weigh 1.0; a weight of 0 leaves the source out. When set, each row
+    /// also reports every source's contribution to its `avg_percentile`.
+    pub weights: Option<HashMap<String, f64>>,
+}
+
+impl AggregateOptions {
+    pub fn weight(&self, source: &str) -> f64 {
+        self.weights
+            .as_ref()
+            .and_then(|weights| {
+                weights
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(source))
+                    .map(|(_, weight)| *weight)
+            })
+            .unwrap_or(1.0)
+            .max(0.0)
+    }
+
+    /// Weight applied to every source in `results` that has ranked rows.
+    pub fn effective_weights(&self, results: &[SourceResult]) -> BTreeMap<String, f64> {
+        results
+            .iter()
+            .filter(|result| result.scores.iter().any(|score| score.rank.is_some()))
+            .map(|result| (result.source.clone(), self.weight(&result.source)))
+            .collect()
+    }
+}
+
+struct AggregateRow {
+    model: String,
+    avg_percentile: f64,
+    spread: f64,
+    sources_count: usize,
+    /// Weighted share of sources that list the model
+    coverage: f64,
+    /// `(source, weight * score / total weight)`, summing to `avg_percentile`
+    contributions: Vec<(String, f64)>,
+}
+
+/// One source's normalised score for a model, in `[0, 1]`, with the weight it
+/// carries in the combined score.
+struct Placement {
+    source: String,
+    score: f64,
+    weight: f64,
+    /// Estimated by `--coverage impute` rather than listed by the source
+    imputed: bool,
+}
+
+/// Resolve every ranked row to its canonical name and keep one row per model per
+/// source, chosen by `policy`. Sources with no ranked rows are dropped.
+///
+/// Ranks are re-assigned over the deduplicated rows so that a model listed as
+/// `claude-opus-4-6`, `claude-opus-4-6-thinking` and `anthropic/claude-opus-4.6`
+/// counts once and doesn't inflate the row count other models are ranked against.
+pub fn resolve_sources(
+    results: &[SourceResult],
+    aliases: &AliasMap,
+    policy: VariantPolicy,
+) -> Vec<ResolvedSource> {
+    let mut resolved = Vec::with_capacity(results.len());
+
+    for source in results {
+        let mut ranked: Vec<&ModelScore> = source
+            .scores
+            .iter()
+            .filter(|score| score.rank.is_some())
+            .collect();
+        if ranked.is_empty() {
+            continue;
+        }
+        ranked.sort_by_key(|score| score.rank);
+
+        let mut by_model: HashMap<String, Vec<&ModelScore>> = HashMap::new();
+        let mut order: Vec<String> = Vec::new();
+        for score in ranked {
+            let canonical = aliases.canonical_for(score);
+            let entry = by_model.entry(canonical.clone()).or_default();
+            if entry.is_empty() {
+                order.push(canonical);
+            }
+            entry.push(score);
+        }
+
+        let mut chosen: Vec<(String, &ModelScore)> = order
+            .into_iter()
+            .map(|model| {
+                let candidates = &by_model[&model];
+                let pick = policy.pick(candidates);
+                (model, pick)
+            })
+            .collect();
+        chosen.sort_by_key(|(_, score)| score.rank);
+
+        let metric = primary_metric(&source.source);
+        let metric_value = |score: &ModelScore| {
+            metric
+                .and_then(|name| score.metrics.get(name))
+                .and_then(metric_as_f64)
+        };
+        let use_metric = chosen
+            .iter()
+            .all(|(_, score)| metric_value(score).is_some());
+        let uncertainty = UNCERTAINTY_METRICS
+            .iter()
+            .find(|(name, _, _)| *name == source.source);
+
+        let mut rows: Vec<ResolvedRow> = chosen
+            .into_iter()
+            .map(|(model, score)| ResolvedRow {
+                model,
+                source_model_name: score.source_model_name.clone(),
+                rank: 0,
+                value: if use_metric {
+                    metric_value(score).unwrap_or_default()
+                } else {
+                    -f64::from(score.rank.unwrap_or(u32::MAX))
+                },
+                std_error: uncertainty
+                    .filter(|_| use_metric)
+                    .and_then(|(_, metric, divisor)| {
+                        score
+                            .metrics
+                            .get(*metric)
+                            .and_then(metric_as_f64)
+                            .map(|v| v / divisor)
+                    }),
+            })
+            .collect();
+        // Equal values share a rank, as they do in the sources themselves
+        let ranks = competition_ranks(rows.iter().map(|row| row.value));
+        for (row, rank) in rows.iter_mut().zip(ranks) {
+            row.rank = rank;
+        }
+
+        resolved.push(ResolvedSource {
+            source: source.source.clone(),
+            metric: metric.filter(|_| use_metric),
+            rows,
+        });
+    }
+
+    resolved
+}
+
+pub fn metric_as_f64(value: &MetricValue) -> Option<f64> {
+    match value {
+        MetricValue::Float(v) => Some(*v),
+        MetricValue::Int(v) => Some(*v as f64),
+        MetricValue::Text(_) => None,
+    }
+}
+
+/// Resolved sources that take part in aggregation: weight-0 sources are left out.
+pub fn aggregate_sources(
+    results: &[SourceResult],
+    aliases: &AliasMap,
+    options: &AggregateOptions,
+) -> Vec<ResolvedSource> {
+    resolve_sources(results, aliases, options.variant)
+        .into_iter()
+        .filter(|source| options.weight(&source.source) > 0.0)
+        .collect()
+}
+
+pub fn aggregate_results(
+    results: &[SourceResult],
+    aliases: &AliasMap,
+    options: &AggregateOptions,
+) -> (SourceResult, Vec<(String, usize)>) {
+    let sources = aggregate_sources(results, aliases, options);
+
+    let mut excluded: Vec<(String, usize)> = Vec::new();
+    let mut rows: Vec<AggregateRow> = score_models(&sources, options)
+        .into_iter()
+        .filter(|row| {
+            if row.sources_count >= options.min_sources {
+                return true;
+            }
+            if options.show_excluded {
+                excluded.push((row.model.clone(), row.sources_count));
+            }
+            false
+        })
+        .collect();
+
+    rows.sort_by(by_score);
+    excluded.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let scores = rows
+        .into_iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut metrics = HashMap::from([
+                (
+                    "avg_percentile".to_string(),
+                    MetricValue::Float(row.avg_percentile),
+                ),
+                ("spread".to_string(), MetricValue::Float(row.spread)),
+                (
+                    "sources_count".to_string(),
+                    MetricValue::Int(row.sources_count as i64),
+                ),
+            ]);
+            if options.coverage != Coverage::None {
+                let method = if row.coverage >= 1.0 {
+                    "full"
+                } else {
+                    options.coverage.name()
+                };
+                metrics.insert(
+                    "coverage_method".to_string(),
+                    MetricValue::Text(method.to_string()),
+                );
+                metrics.insert("coverage".to_string(), MetricValue::Float(row.coverage));
+            }
+            if options.weights.is_some() && options.method.is_additive() {
+                for (source, contribution) in row.contributions {
+                    metrics.insert(
+                        format!("contrib.{source}"),
+                        MetricValue::Float(contribution),
+                    );
+                }
+            }
+            ModelScore {
+                model: row.model.clone(),
+                source_model_name: row.model,
+                variant: Variant::default(),
+                agent: None,
+                metrics,
+                rank: Some((i + 1) as u32),
+            }
+        })
+        .collect();
+
+    (
+        SourceResult {
+            source: "aggregate".to_string(),
+            fetched_at: None,
+            status: SourceStatus::Ok,
+            scores,
+        },
+        if options.show_excluded {
+            excluded
+        } else {
+            Vec::new()
+        },
+    )
+}
+
+/// How one source feeds into a model's aggregate score.
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceBreakdown {
+    pub source: String,
+    /// The row the variant policy picked, as the source names it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_model_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<u32>,
+    pub total: usize,
+    /// The model's normalised score in this source under the chosen method
+    /// (its percentile for `percentile`); not reported for imputed sources
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    /// The source's weight from the profile (1.0 without one)
+    pub weight: f64,
+    /// This source's share of `avg_percentile`, for additive methods
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contribution: Option<f64>,
+    /// Estimated by `--coverage impute` rather than listed by the source
+    pub imputed: bool,
+}
+
+/// Everything that went into one model's row of `rank --aggregate`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelBreakdown {
+    pub model: String,
+    /// Position in the aggregate, or `None` when `min_sources` excludes it
+    pub rank: Option<usize>,
+    /// Number of models that make the cut
+    pub ranked: usize,
+    pub avg_percentile: f64,
+    pub spread: f64,
+    pub sources_count: usize,
+    pub coverage: f64,
+    pub sources: Vec<SourceBreakdown>,
+    /// Pull toward the mean from `--coverage shrink`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shrinkage: Option<f64>,
+}
+
+/// Break `model`'s aggregate score down by source, computed exactly as
+/// [`aggregate_results`] does. `None` if no source lists the model.
+pub fn explain_model(
+    results: &[SourceResult],
+    aliases: &AliasMap,
+    options: &AggregateOptions,
+    model: &str,
+) -> Option<ModelBreakdown> {
+    let sources = aggregate_sources(results, aliases, options);
+    let mut rows = score_models(&sources, options);
+    rows.sort_by(by_score);
+    let ranked: Vec<&AggregateRow> = rows
+        .iter()
+        .filter(|row| row.sources_count >= options.min_sources)
+        .collect();
+    let rank = ranked
+        .iter()
+        .position(|row| row.model == model)
+        .map(|i| i + 1);
+    let row = rows.iter().find(|row| row.model == model)?;
+
+    let contribution = |source: &str| {
+        options
+            .method
+            .is_additive()
+            .then(|| {
+                row.contributions
+                    .iter()
+                    .find(|(name, _)| name == source)
+                    .map(|(_, c)| *c)
+            })
+            .flatten()
+    };
+
+    let mut breakdown = Vec::new();
+    for source in &sources {
+        let weight = options.weight(&source.source);
+        let listed = source.rows.iter().find(|r| r.model == model);
+        let imputed = listed.is_none()
+            && row
+                .contributions
+                .iter()
+                .any(|(name, _)| *name == source.source);
+        if listed.is_none() && !imputed {
+            continue;
+        }
+        let score = listed.and_then(|_| {
+            source_placements(source, options.method, weight)
+                .into_iter()
+                .find(|(name, _, _)| name == model)
+                .map(|(_, score, _)| score)
+        });
+        breakdown.push(SourceBreakdown {
+            source: source.source.clone(),
+            source_model_name: listed.map(|r| r.source_model_name.clone()),
+            metric: listed.and(source.metric),
+            value: listed.filter(|_| source.metric.is_some()).map(|r| r.value),
+            rank: listed.map(|r| r.rank),
+            total: source.total(),
+            score,
+            weight,
+            contribution: contribution(&source.source),
+            imputed,
+        });
+    }
+
+    Some(ModelBreakdown {
+        model: row.model.clone(),
+        rank,
+        ranked: ranked.len(),
+        avg_percentile: row.avg_percentile,
+        spread: row.spread,
+        sources_count: row.sources_count,
+        coverage: row.coverage,
+        sources: breakdown,
+        shrinkage: row
+            .contributions
+            .iter()
+            .find(|(name, _)| name == "shrinkage")
+            .map(|(_, c)| *c),
+    })
+}
+
+/// Best combined score first, then by name.
+fn by_score(a: &AggregateRow, b: &AggregateRow) -> Ordering {
+    b.avg_percentile
+        .partial_cmp(&a.avg_percentile)
+        .unwrap_or(Ordering::Equal)
+        .then_with(|| a.model.cmp(&b.model))
+}
+
+/// Combined score of every model listed by at least one of `sources`.
+pub fn combined_scores(
+    sources: &[ResolvedSource],
+    options: &AggregateOptions,
+) -> HashMap<String, f64> {
+    score_models(sources, options)
+        .into_iter()
+        .map(|row| (row.model, row.avg_percentile))
+        .collect()
+}
+
+fn score_models(sources: &[ResolvedSource], options: &AggregateOptions) -> Vec<AggregateRow> {
+    let mut totals: HashMap<String, Vec<Placement>> = HashMap::new();
+
+    for source in sources {
+        let weight = options.weight(&source.source);
+        for (model, score, weight) in source_placements(source, options.method, weight) {
+            totals.entry(model).or_default().push(Placement {
+                source: source.source.clone(),
+                score,
+                weight,
+                imputed: false,
+            });
+        }
+    }
+
+    let total_source_weight: f64 = sources.iter().map(|s| options.weight(&s.source)).sum();
+    if options.coverage == Coverage::Impute {
+        impute_missing(&mut totals, options.method);
+    }
+
+    let strengths =
+        (options.method == AggregateMethod::BradleyTerry).then(|| bradley_terry(sources, options));
+
+    let mut rows: Vec<AggregateRow> = totals
+        .into_iter()
+        .map(|(model, placements)| {
+            let total_weight: f64 = placements.iter().map(|p| p.weight).sum();
+            let contributions: Vec<(String, f64)> = placements
+                .iter()
+                .map(|p| (p.source.clone(), p.weight * p.score / total_weight))
+                .collect();
+            let avg = match options.method {
+                AggregateMethod::RankProduct => {
+                    // Scores are 1 - q; combine the q's geometrically
+                    let log_mean = placements
+                        .iter()
+                        .map(|p| p.weight * (1.0 - p.score).ln())
+                        .sum::<f64>()
+                        / total_weight;
+                    1.0 - log_mean.exp()
+                }
+                AggregateMethod::BradleyTerry => strengths
+                    .as_ref()
+                    .and_then(|s| s.get(&model))
+                    .copied()
+                    .unwrap_or(0.5),
+                _ => contributions.iter().map(|(_, c)| c).sum(),
+            };
+            let listed: Vec<&Placement> = placements.iter().filter(|p| !p.imputed).collect();
+            let scores: Vec<f64> = listed.iter().map(|p| p.score).collect();
+            let covered_weight: f64 = listed.iter().map(|p| options.weight(&p.source)).sum();
+            AggregateRow {
+                model,
+                avg_percentile: avg,
+                spread: std_dev(&scores),
+                sources_count: listed.len(),
+                coverage: if total_source_weight > 0.0 {
+                    covered_weight / total_source_weight
+                } else {
+                    1.0
+                },
+                contributions,
+            }
+        })
+        .collect();
+
+    if options.coverage == Coverage::Shrink && !rows.is_empty() {
+        let grand_mean = rows.iter().map(|r| r.avg_percentile).sum::<f64>() / rows.len() as f64;
+        for row in &mut rows {
+            row.avg_percentile = grand_mean + (row.avg_percentile - grand_mean) * row.coverage;
+            for (_, contribution) in &mut row.contributions {
+                *contribution *= row.coverage;
+            }
+            row.contributions
+                .push(("shrinkage".to_string(), grand_mean * (1.0 - row.coverage)));
+        }
+    }
+
+    rows
+}
+
+/// Add an estimated placement for every source that doesn't list a model.
+///
+/// For each pair of sources sharing at least three models, a least-squares line
+/// predicts the target source's score from the other's. A missing score is the
+/// r²-weighted mean of the predictions from positively correlated sources the
+/// model is listed in, or the target source's mean score when there are none.
+fn impute_missing(totals: &mut HashMap<String, Vec<Placement>>, method: AggregateMethod) {
+    // source → (model → score), and the weight a placement in that source carries
+    let mut by_source: HashMap<String, HashMap<String, f64>> = HashMap::new();
+    let mut source_weight: HashMap<String, f64> = HashMap::new();
+    for (model, placements) in totals.iter() {
+        for p in placements {
+            by_source
+                .entry(p.source.clone())
+                .or_default()
+                .insert(model.clone(), p.score);
+            source_weight.insert(p.source.clone(), p.weight);
+        }
+    }
+
+    let mut fits: HashMap<(&str, &str), (f64, f64, f64)> = HashMap::new();
+    for (target, target_scores) in &by_source {
+        for (predictor, predictor_scores) in &by_source {
+            if target == predictor {
+                continue;
+            }
+            let pairs: Vec<(f64, f64)> = predictor_scores
+                .iter()
+                .filter_map(|(model, x)| target_scores.get(model).map(|y| (*x, *y)))
+                .collect();
+            if let Some(fit) = linear_fit(&pairs)
+                && fit.2 > 0.0
+            {
+                fits.insert((target.as_str(), predictor.as_str()), fit);
+            }
+        }
+    }
+
+    // RankProduct takes ln(1 - score), so keep imputed scores off 1.0
+    let max_score = if method == AggregateMethod::RankProduct {
+        0.999
+    } else {
+        1.0
+    };
+
+    let mut sources: Vec<&String> = by_source.keys().collect();
+    sources.sort();
+    for placements in totals.values_mut() {
+        let mut imputed = Vec::new();
+        for target in &sources {
+            if placements.iter().any(|p| &p.source == *target) {
+                continue;
+            }
+            let (mut sum, mut weight) = (0.0, 0.0);
+            for p in placements.iter() {
+                if let Some((intercept, slope, r)) = fits.get(&(target.as_str(), p.source.as_str()))
+                {
+                    sum += r * r * (intercept + slope * p.score);
+                    weight += r * r;
+                }
+            }
+            let score = if weight > 0.0 {
+                sum / weight
+            } else {
+                let target_scores = &by_source[*target];
+                target_scores.values().sum::<f64>() / target_scores.len() as f64
+            };
+            imputed.push(Placement {
+                source: (*target).clone(),
+                score: score.clamp(0.0, max_score),
+                weight: source_weight[*target],
+                imputed: true,
+            });
+        }
+        placements.extend(imputed);
+    }
+}
+
+/// Least-squares `y = intercept + slope * x` and Pearson r, over at least three
+/// points with non-zero variance in both coordinates.
+fn linear_fit(points: &[(f64, f64)]) -> Option<(f64, f64, f64)> {
+    if points.len() < 3 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let cov: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    let var_x: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    let var_y: f64 = points.iter().map(|p| (p.1 - mean_y).powi(2)).sum();
+    if var_x == 0.0 || var_y == 0.0 {
+        return None;
+    }
+    let slope = cov / var_x;
+    Some((mean_y - slope * mean_x, slope, cov / (var_x * var_y).sqrt()))
+}
+
+/// Each model's normalised score within one source, with the weight it carries.
+fn source_placements(
+    source: &ResolvedSource,
+    method: AggregateMethod,
+    weight: f64,
+) -> Vec<(String, f64, f64)> {
+    let total = source.total();
+    let values: Vec<f64> = source.rows.iter().map(|row| row.value).collect();
+    let mean = values.iter().sum::<f64>() / total as f64;
+    let sd = std_dev(&values);
+
+    source
+        .rows
+        .iter()
+        .map(|row| {
+            let (score, weight) = match method {
+                // Bradley–Terry is fitted globally; per-source percentiles only
+                // feed `spread` and the source count.
+                AggregateMethod::Percentile | AggregateMethod::BradleyTerry => {
+                    (percentile(mid_rank(row.value, &values), total), weight)
+                }
+                AggregateMethod::Zscore => {
+                    let z = if sd > 0.0 {
+                        (row.value - mean) / sd
+                    } else {
+                        0.0
+                    };
+                    (normal_cdf(z), weight)
+                }
+                AggregateMethod::Borda => {
+                    // Points = models beaten, ties half a point. A source's weight
+                    // scales with the points it has to give out.
+                    let points = borda_points(row.value, &values);
+                    let available = total.saturating_sub(1).max(1) as f64;
+                    let share = if total <= 1 { 1.0 } else { points / available };
+                    (share, weight * available)
+                }
+                AggregateMethod::RankProduct => {
+                    let q = (mid_rank(row.value, &values) - 0.5) / total as f64;
+                    (1.0 - q, weight)
+                }
+            };
+            (row.model.clone(), score, weight)
+        })
+        .collect()
+}
+
+fn borda_points(value: f64, values: &[f64]) -> f64 {
+    let below = values.iter().filter(|v| **v < value).count() as f64;
+    let tied = values.iter().filter(|v| **v == value).count() as f64 - 1.0;
+    below + tied / 2.0
+}
+
+/// 1-based rank of `value` among `values` (higher is better), ties sharing the
+/// mean of the positions they span.
+fn mid_rank(value: f64, values: &[f64]) -> f64 {
+    let above = values.iter().filter(|v| **v > value).count() as f64;
+    let tied = values.iter().filter(|v| **v == value).count() as f64;
+    above + (tied + 1.0) / 2.0
+}
+
+/// Fit Bradley–Terry strengths to pairwise outcomes pooled across sources, using
+/// Hunter's MM iteration, and return each model's mean win probability against
+/// every other model.
+///
+/// Within a source every pair of listed models is one comparison, weighted by
+/// the source weight; a tie counts half a win each way. Each model also plays
+/// one virtual game (half win, half loss) against a unit-strength reference so
+/// that unbeaten or winless models keep a finite strength.
+fn bradley_terry(sources: &[ResolvedSource], options: &AggregateOptions) -> HashMap<String, f64> {
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    for source in sources {
+        for row in &source.rows {
+            let next = index.len();
+            index.entry(row.model.as_str()).or_insert(next);
+        }
+    }
+    let n = index.len();
+    if n == 0 {
+        return HashMap::new();
+    }
+
+    // wins[i] = total (weighted) wins; games[i][j] = comparisons between i and j
+    let mut wins = vec![0.5; n];
+    let mut games = vec![vec![0.0; n]; n];
+    for source in sources {
+        let weight = options.weight(&source.source);
+        for (a, row_a) in source.rows.iter().enumerate() {
+            for row_b in &source.rows[a + 1..] {
+                let i = index[row_a.model.as_str()];
+                let j = index[row_b.model.as_str()];
+                games[i][j] += weight;
+                games[j][i] += weight;
+                match row_a.value.partial_cmp(&row_b.value) {
+                    Some(Ordering::Greater) => wins[i] += weight,
+                    Some(Ordering::Less) => wins[j] += weight,
+                    _ => {
+                        wins[i] += weight / 2.0;
+                        wins[j] += weight / 2.0;
+                    }
+                }
+            }
+        }
+    }
+
+    let mut strength = vec![1.0; n];
+    for _ in 0..1000 {
+        let mut next = vec![0.0; n];
+        for i in 0..n {
+            // Virtual game against the reference (strength 1.0)
+            let mut denom = 1.0 / (strength[i] + 1.0);
+            for j in 0..n {
+                if games[i][j] > 0.0 {
+                    denom += games[i][j] / (strength[i] + strength[j]);
+                }
+            }
+            next[i] = wins[i] / denom;
+        }
+        let delta = next
+            .iter()
+            .zip(&strength)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max);
+        strength = next;
+        if delta < 1e-10 {
+            break;
+        }
+    }
+
+    index
+        .into_iter()
+        .map(|(model, i)| {
+            let win_probability = if n == 1 {
+                0.5
+            } else {
+                (0..n)
+                    .filter(|j| *j != i)
+                    .map(|j| strength[i] / (strength[i] + strength[j]))
+                    .sum::<f64>()
+                    / (n - 1) as f64
+            };
+            (model.to_string(), win_probability)
+        })
+        .collect()
+}
+
+/// Standard normal CDF via the Abramowitz–Stegun erf approximation (7.1.26),
+/// accurate to about 1e-7.
+fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+pub fn excluded_models(
+    results: &[SourceResult],
+    aliases: &AliasMap,
+    options: &AggregateOptions,
+) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for source in resolve_sources(results, aliases, options.variant) {
+        if options.weight(&source.source) == 0.0 {
+            continue;
+        }
+        for row in source.rows {
+            *counts.entry(row.model).or_insert(0) += 1;
+        }
+    }
+
+    let mut excluded: Vec<(String, usize)> = counts
+        .into_iter()
+        .filter(|(_, count)| *count < options.min_sources)
+        .collect();
+    excluded.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    excluded
+}
+
+/// Share of the other models in a source that `rank` is ahead of: 1.0 for
+/// first place, 0.0 for last. Pass a mid-rank for tied models.
+pub fn percentile(rank: f64, total: usize) -> f64 {
+    if total <= 1 {
+        1.0
+    } else {
+        (total as f64 - rank) / (total as f64 - 1.0)
+    }
+}
+
+pub fn std_dev(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variant::parse_variant;
+
+    fn options(min_sources: usize, show_excluded: bool) -> AggregateOptions {
+        AggregateOptions {
+            min_sources,
+            show_excluded,
+            variant: VariantPolicy::Best,
+            method: AggregateMethod::Percentile,
+            coverage: Coverage::None,
+            weights: None,
+        }
+    }
+
+    fn score(model: &str, rank: u32) -> ModelScore {
+        ModelScore {
+            model: model.to_string(),
+            source_model_name: model.to_string(),
+            variant: parse_variant(model),
+            agent: None,
+            metrics: HashMap::new(),
+            rank: Some(rank),
+        }
+    }
+
+    fn make_source_with_ranked_model(
+        source: &str,
+        model: &str,
+        rank: u32,
+        total: usize,
+    ) -> SourceResult {
+        let mut scores = Vec::with_capacity(total);
+        scores.push(score(model, rank));
+
+        for i in 1..total {
+            let filler_rank = if i as u32 >= rank {
+                i as u32 + 1
+            } else {
+                i as u32
+            };
+            scores.push(score(&format!("filler-{source}-{i}"), filler_rank));
+        }
+
+        SourceResult {
+            source: source.to_string(),
+            fetched_at: None,
+            status: SourceStatus::Ok,
+            scores,
+        }
+    }
+
+    fn rank_of(source: &ResolvedSource, model: &str) -> u32 {
+        source
+            .rows
+            .iter()
+            .find(|row| row.model == model)
+            .unwrap()
+            .rank
+    }
+
+    fn find_model<'a>(scores: &'a [ModelScore], model: &str) -> &'a ModelScore {
+        scores.iter().find(|s| s.model == model).unwrap()
+    }
+
+    fn metric_float(score: &ModelScore, name: &str) -> f64 {
+        match score.metrics.get(name).unwrap() {
+            MetricValue::Float(v) => *v,
+            _ => panic!("metric {name} is not float"),
+        }
+    }
+
+    fn metric_int(score: &ModelScore, name: &str) -> i64 {
+        match score.metrics.get(name).unwrap() {
+            MetricValue::Int(v) => *v,
+            _ => panic!("metric {name} is not int"),
+        }
+    }
+
+    #[test]
+    fn percentile_rank_1_of_10_is_1() {
+        assert!((percentile(1.0, 10) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn percentile_rank_10_of_10_is_0() {
+        assert!((percentile(10.0, 10) - 0.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn percentile_rank_1_of_1_is_1() {
+        assert!((percentile(1.0, 1) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn percentile_rank_5_of_10_matches_expected_formula() {
+        let expected = (10.0 - 5.0) / (10.0 - 1.0);
+        assert!((percentile(5.0, 10) - expected).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn two_sources_correct_average() {
+        let model = "model-a";
+        let source_a = make_source_with_ranked_model("source-a", model, 1, 10);
+        let source_b = make_source_with_ranked_model("source-b", model, 5, 10);
+
+        let (aggregated, excluded) = aggregate_results(
+            &[source_a, source_b],
+            &AliasMap::bundled(),
+            &options(2, false),
+        );
+        assert!(excluded.is_empty());
+
+        let score = find_model(&aggregated.scores, model);
+        let expected = (1.0 + (5.0 / 9.0)) / 2.0;
+        assert!((metric_float(score, "avg_percentile") - expected).abs() < 0.001);
+        assert_eq!(metric_int(score, "sources_count"), 2);
+    }
+
+    #[test]
+    fn min_sources_filters() {
+        let model_a = "model-a";
+        let model_b = "model-b";
+
+        let mut source_a = make_source_with_ranked_model("source-a", model_a, 1, 10);
+        source_a.scores[1] = score(model_b, 2);
+        let source_b = make_source_with_ranked_model("source-b", model_a, 3, 10);
+
+        let (aggregated, excluded) = aggregate_results(
+            &[source_a, source_b],
+            &AliasMap::bundled(),
+            &options(2, true),
+        );
+
+        assert!(aggregated.scores.iter().all(|s| s.model != model_b));
+        assert!(excluded.iter().any(|(m, c)| m == model_b && *c == 1));
+    }
+
+    #[test]
+    fn spread_single_source_is_zero() {
+        let model = "model-a";
+        let source = make_source_with_ranked_model("source-a", model, 4, 10);
+
+        let (aggregated, _) =
+            aggregate_results(&[source], &AliasMap::bundled(), &options(1, false));
+        let score = find_model(&aggregated.scores, model);
+        assert!((metric_float(score, "spread") - 0.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn spread_two_sources() {
+        let model = "model-a";
+        let source_a = make_source_with_ranked_model("source-a", model, 1, 10);
+        let source_b = make_source_with_ranked_model("source-b", model, 5, 10);
+
+        let (aggregated, _) = aggregate_results(
+            &[source_a, source_b],
+            &AliasMap::bundled(),
+            &options(2, false),
+        );
+        let score = find_model(&aggregated.scores, model);
+        let expected = 0.222;
+        assert!((metric_float(score, "spread") - expected).abs() < 0.001);
+    }
+
+    #[test]
+    fn show_excluded_false_returns_empty() {
+        let model_a = "model-a";
+        let model_b = "model-b";
+
+        let mut source_a = make_source_with_ranked_model("source-a", model_a, 1, 10);
+        source_a.scores[1] = score(model_b, 2);
+        let source_b = make_source_with_ranked_model("source-b", model_a, 3, 10);
+
+        let (_, excluded) = aggregate_results(
+            &[source_a, source_b],
+            &AliasMap::bundled(),
+            &options(2, false),
+        );
+        assert!(excluded.is_empty());
+    }
+
+    #[test]
+    fn std_dev_known_values() {
+        assert!((std_dev(&[1.0, 3.0]) - 1.0).abs() < f64::EPSILON);
+        assert!((std_dev(&[1.0]) - 0.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn variant_rows_collapse_to_one_canonical() {
+        // Three spellings of one model plus two others: the model must count once
+        // and the source must be ranked out of 3 rows, not 5.
+        let source = SourceResult {
+            source: "arena".to_string(),
+            fetched_at: None,
+            status: SourceStatus::Ok,
+            scores: vec![
+                score("claude-opus-4.6-(max)", 1),
+                score("gpt-5.2", 2),
+                score("claude-opus-4-6", 3),
+                score("anthropic/claude-opus-4.6", 4),
+                score("model-z", 5),
+            ],
+        };
+
+        let resolved = resolve_sources(&[source], &AliasMap::bundled(), VariantPolicy::Best);
+        assert_eq!(resolved[0].total(), 3);
+        assert_eq!(rank_of(&resolved[0], "claude-opus-4.6"), 1);
+        assert_eq!(rank_of(&resolved[0], "model-z"), 3);
+    }
+
+    #[test]
+    fn variant_policy_prefers_requested_effort() {
+        let source = SourceResult {
+            source: "arena".to_string(),
+            fetched_at: None,
+            status: SourceStatus::Ok,
+            scores: vec![
+                score("claude-opus-4.6-(max)", 1),
+                score("gpt-5.2", 2),
+                score("claude-opus-4-6", 3),
+            ],
+        };
+
+        let resolved = resolve_sources(&[source], &AliasMap::bundled(), VariantPolicy::Standard);
+        // gpt-5.2 now outranks the standard-effort Opus row
+        assert_eq!(rank_of(&resolved[0], "claude-opus-4.6"), 2);
+        assert_eq!(rank_of(&resolved[0], "gpt-5.2"), 1);
+    }
+
+    #[test]
+    fn profile_weights_shift_average_and_contributions_sum() {
+        let model = "model-a";
+        let source_a = make_source_with_ranked_model("source-a", model, 1, 10);
+        let source_b = make_source_with_ranked_model("source-b", model, 10, 10);
+
+        let mut options = options(2, false);
+        options.weights = Some(HashMap::from([("source-a".to_string(), 3.0)]));
+        let (aggregated, _) =
+            aggregate_results(&[source_a, source_b], &AliasMap::bundled(), &options);
+
+        let score = find_model(&aggregated.scores, model);
+        // (3 * 1.0 + 1 * 0.0) / 4
+        assert!((metric_float(score, "avg_percentile") - 0.75).abs() < 0.001);
+        let contrib_a = metric_float(score, "contrib.source-a");
+        let contrib_b = metric_float(score, "contrib.source-b");
+        assert!((contrib_a - 0.75).abs() < 0.001);
+        assert!((contrib_a + contrib_b - metric_float(score, "avg_percentile")).abs() < 1e-9);
+    }
+
+    #[test]
+    fn explain_model_matches_the_aggregate_row() {
+        let sources = [
+            source_with_values("swebench", &[("a", 80.0), ("b", 70.0), ("c", 60.0)]),
+            source_with_values("aider", &[("b", 90.0), ("a", 85.0)]),
+            source_with_values("arena", &[("d", 1400.0)]),
+        ];
+        let mut options = options(2, false);
+        options.weights = Some(HashMap::from([("swebench".to_string(), 2.0)]));
+
+        let breakdown = explain_model(&sources, &AliasMap::bundled(), &options, "a").unwrap();
+        let (aggregated, _) = aggregate_results(&sources, &AliasMap::bundled(), &options);
+        let row = find_model(&aggregated.scores, "a");
+
+        assert_eq!(breakdown.rank, row.rank.map(|rank| rank as usize));
+        assert_eq!(breakdown.ranked, 2);
+        assert!((breakdown.avg_percentile - metric_float(row, "avg_percentile")).abs() < 1e-9);
+        let contributions: f64 = breakdown
+            .sources
+            .iter()
+            .filter_map(|s| s.contribution)
+            .sum();
+        assert!((contributions - breakdown.avg_percentile).abs() < 1e-9);
+
+        let listed: Vec<(&str, Option<u32>, f64)> = breakdown
+            .sources
+            .iter()
+            .map(|s| (s.source.as_str(), s.rank, s.weight))
+            .collect();
+        assert_eq!(
+            listed,
+            vec![("swebench", Some(1), 2.0), ("aider", Some(2), 1.0)]
+        );
+        assert!(explain_model(&sources, &AliasMap::bundled(), &options, "missing").is_none());
+        // Listed by one source only: explained, but below --min-sources
+        assert_eq!(
+            explain_model(&sources, &AliasMap::bundled(), &options, "c")
+                .unwrap()
+                .rank,
+            None
+        );
+    }
+
+    #[test]
+    fn zero_weight_drops_source() {
+        let model = "model-a";
+        let source_a = make_source_with_ranked_model("source-a", model, 1, 10);
+        let source_b = make_source_with_ranked_model("source-b", model, 10, 10);
+
+        let mut options = options(1, false);
+        options.weights = Some(HashMap::from([("SOURCE-B".to_string(), 0.0)]));
+        let (aggregated, _) =
+            aggregate_results(&[source_a, source_b], &AliasMap::bundled(), &options);
+
+        let score = find_model(&aggregated.scores, model);
+        assert_eq!(metric_int(score, "sources_count"), 1);
+        assert!((metric_float(score, "avg_percentile") - 1.0).abs() < f64::EPSILON);
+    }
+
+    /// A source listing `(model, primary metric value)` pairs, best first.
+    fn source_with_values(source: &str, rows: &[(&str, f64)]) -> SourceResult {
+        let metric = primary_metric(source).unwrap();
+        SourceResult {
+            source: source.to_string(),
+            fetched_at: None,
+            status: SourceStatus::Ok,
+            scores: rows
+                .iter()
+                .enumerate()
+                .map(|(i, (model, value))| ModelScore {
+                    metrics: HashMap::from([(metric.to_string(), MetricValue::Float(*value))]),
+                    ..score(model, (i + 1) as u32)
+                })
+                .collect(),
+        }
+    }
+
+    fn aggregate_with(method: AggregateMethod, sources: &[SourceResult]) -> SourceResult {
+        let mut options = options(1, false);
+        options.method = method;
+        aggregate_results(sources, &AliasMap::bundled(), &options).0
+    }
+
+    #[test]
+    fn tied_values_share_rank_and_mid_rank_percentile() {
+        let tbench = source_with_values(
+            "terminal-bench",
+            &[("a", 60.0), ("b", 50.0), ("c", 50.0), ("d", 40.0)],
+        );
+
+        let resolved = resolve_sources(
+            std::slice::from_ref(&tbench),
+            &AliasMap::bundled(),
+            VariantPolicy::Best,
+        );
+        assert_eq!(rank_of(&resolved[0], "b"), 2);
+        assert_eq!(rank_of(&resolved[0], "c"), 2);
+        assert_eq!(rank_of(&resolved[0], "d"), 4);
+
+        let aggregated = aggregate_with(AggregateMethod::Percentile, &[tbench]);
+        // b and c span positions 2–3, so both sit at mid-rank 2.5 of 4
+        for model in ["b", "c"] {
+            let pct = metric_float(find_model(&aggregated.scores, model), "avg_percentile");
+            assert!((pct - 0.5).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn zscore_two_sources_correct_average() {
+        let arena = source_with_values("arena", &[("x", 1300.0), ("y", 1200.0), ("z", 1100.0)]);
+        let seal = source_with_values("seal", &[("x", 90.0), ("y", 60.0), ("z", 0.0)]);
+
+        let aggregated = aggregate_with(AggregateMethod::Zscore, &[arena, seal]);
+        let x = find_model(&aggregated.scores, "x");
+        // arena z = 1.2247 → Φ = 0.8897; seal: mean 50, sd 37.417, z = 1.069 → Φ = 0.8575
+        let expected = (0.8897 + 0.8575) / 2.0;
+        assert!((metric_float(x, "avg_percentile") - expected).abs() < 0.001);
+        // Raw gaps matter: y sits closer to x on seal than on arena
+        let y = find_model(&aggregated.scores, "y");
+        assert!((metric_float(y, "avg_percentile") - (0.5 + 0.6054) / 2.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn borda_weights_sources_by_points_available() {
+        let arena = source_with_values("arena", &[("x", 3.0), ("y", 2.0), ("z", 1.0)]);
+        let seal = source_with_values("seal", &[("y", 2.0), ("x", 1.0)]);
+
+        let aggregated = aggregate_with(AggregateMethod::Borda, &[arena, seal]);
+        // x: 2 of 2 points on arena + 0 of 1 on seal = 2/3
+        assert!(
+            (metric_float(find_model(&aggregated.scores, "x"), "avg_percentile") - 2.0 / 3.0).abs()
+                < 0.001
+        );
+        // y: 1 + 1 of 3
+        assert!(
+            (metric_float(find_model(&aggregated.scores, "y"), "avg_percentile") - 2.0 / 3.0).abs()
+                < 0.001
+        );
+        assert!(metric_float(find_model(&aggregated.scores, "z"), "avg_percentile").abs() < 0.001);
+    }
+
+    #[test]
+    fn borda_ties_split_points() {
+        let arena = source_with_values("arena", &[("x", 2.0), ("y", 2.0), ("z", 1.0)]);
+
+        let aggregated = aggregate_with(AggregateMethod::Borda, &[arena]);
+        // 1 beaten + half of the tie = 1.5 of 2 points
+        assert!(
+            (metric_float(find_model(&aggregated.scores, "x"), "avg_percentile") - 0.75).abs()
+                < 0.001
+        );
+        assert!(
+            (metric_float(find_model(&aggregated.scores, "y"), "avg_percentile") - 0.75).abs()
+                < 0.001
+        );
+    }
+
+    #[test]
+    fn rank_product_two_sources_correct_average() {
+        let arena = source_with_values("arena", &[("x", 3.0), ("y", 2.0), ("z", 1.0)]);
+        let seal = source_with_values("seal", &[("y", 2.0), ("x", 1.0)]);
+
+        let aggregated = aggregate_with(AggregateMethod::RankProduct, &[arena, seal]);
+        // q = (rank - 0.5) / n: x is 1/6 on arena and 3/4 on seal
+        let expected = 1.0 - (1.0_f64 / 6.0 * 0.75).sqrt();
+        let x = find_model(&aggregated.scores, "x");
+        assert!((metric_float(x, "avg_percentile") - expected).abs() < 0.001);
+    }
+
+    #[test]
+    fn bradley_terry_split_decision_is_even() {
+        let arena = source_with_values("arena", &[("x", 2.0), ("y", 1.0)]);
+        let seal = source_with_values("seal", &[("y", 2.0), ("x", 1.0)]);
+
+        let aggregated = aggregate_with(AggregateMethod::BradleyTerry, &[arena, seal]);
+        for model in ["x", "y"] {
+            let score = find_model(&aggregated.scores, model);
+            assert!((metric_float(score, "avg_percentile") - 0.5).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn bradley_terry_orders_consistent_winner_first() {
+        let arena = source_with_values("arena", &[("x", 3.0), ("y", 2.0), ("z", 1.0)]);
+        let seal = source_with_values("seal", &[("x", 3.0), ("z", 2.0), ("y", 1.0)]);
+
+        let aggregated = aggregate_with(AggregateMethod::BradleyTerry, &[arena, seal]);
+        assert_eq!(aggregated.scores[0].model, "x");
+        let x = metric_float(&aggregated.scores[0], "avg_percentile");
+        let y = metric_float(find_model(&aggregated.scores, "y"), "avg_percentile");
+        let z = metric_float(find_model(&aggregated.scores, "z"), "avg_percentile");
+        assert!(x > 0.5 && x < 1.0);
+        assert!((y - z).abs() < 0.001);
+        // Mean win probabilities of a round robin average to one half
+        assert!(((x + y + z) / 3.0 - 0.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn normal_cdf_known_values() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-4);
+        assert!((normal_cdf(-1.0) - 0.158_655).abs() < 1e-5);
+    }
+
+    #[test]
+    fn shrink_pulls_sparse_models_toward_the_mean() {
+        let a = source_with_values("arena", &[("x", 3.0), ("y", 2.0), ("z", 1.0)]);
+        let b = source_with_values("seal", &[("y", 2.0), ("z", 1.0)]);
+        let c = source_with_values("aider", &[("y", 2.0), ("z", 1.0)]);
+
+        let mut options = options(1, false);
+        options.coverage = Coverage::Shrink;
+        let (aggregated, _) = aggregate_results(&[a, b, c], &AliasMap::bundled(), &options);
+
+        // Unshrunk: x = 1.0, y = 0.833, z = 0.0; grand mean 0.611.
+        // x is listed by 1 of 3 sources: 0.611 + (1.0 - 0.611) / 3 = 0.741
+        assert_eq!(aggregated.scores[0].model, "y");
+        let x = find_model(&aggregated.scores, "x");
+        assert!((metric_float(x, "avg_percentile") - 0.7407).abs() < 0.001);
+        assert!(
+            matches!(x.metrics.get("coverage_method"), Some(MetricValue::Text(m)) if m == "shrink")
+        );
+        let y = find_model(&aggregated.scores, "y");
+        assert!(
+            matches!(y.metrics.get("coverage_method"), Some(MetricValue::Text(m)) if m == "full")
+        );
+    }
+
+    #[test]
+    fn impute_predicts_missing_source_from_correlated_one() {
+        let a = source_with_values(
+            "arena",
+            &[("m1", 4.0), ("m2", 3.0), ("m3", 2.0), ("m4", 1.0)],
+        );
+        let b = source_with_values("seal", &[("m1", 3.0), ("m2", 2.0), ("m3", 1.0)]);
+
+        let mut options = options(1, false);
+        options.coverage = Coverage::Impute;
+        let (aggregated, _) = aggregate_results(&[a, b], &AliasMap::bundled(), &options);
+
+        // seal = 1.5 * arena - 0.5 over m1..m3, so m4 (arena 0.0) is imputed at
+        // -0.5, clamped to 0.0
+        let m4 = find_model(&aggregated.scores, "m4");
+        assert!(metric_float(m4, "avg_percentile").abs() < 0.001);
+        assert_eq!(metric_int(m4, "sources_count"), 1);
+        assert!(
+            matches!(m4.metrics.get("coverage_method"), Some(MetricValue::Text(m)) if m == "impute")
+        );
+        assert!((metric_float(m4, "coverage") - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn impute_falls_back_to_source_mean_without_predictors() {
+        let a = source_with_values("arena", &[("x", 2.0), ("y", 1.0)]);
+        let b = source_with_values("seal", &[("y", 2.0), ("z", 1.0)]);
+
+        let mut options = options(1, false);
+        options.coverage = Coverage::Impute;
+        let (aggregated, _) = aggregate_results(&[a, b], &AliasMap::bundled(), &options);
+
+        // Too little overlap to fit: x gets seal's mean (0.5) → (1.0 + 0.5) / 2
+        let x = find_model(&aggregated.scores, "x");
+        assert!((metric_float(x, "avg_percentile") - 0.75).abs() < 0.001);
+    }
+}
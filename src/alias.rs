@@ -1,6 +1,7 @@
+use crate::models::ModelScore;
 use anyhow::Result;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
 const BUNDLED_ALIASES: &str = include_str!("../models.toml");
@@ -10,6 +11,9 @@ struct AliasEntry {
     canonical: String,
     #[serde(default)]
     aliases: Vec<String>,
+    /// Lineage this model belongs to, e.g. `claude-sonnet` for every Sonnet release.
+    #[serde(default)]
+    family: Option<String>,
 }
 
 pub enum MatchKind {
@@ -19,6 +23,17 @@ pub enum MatchKind {
     NoMatch,
 }
 
+impl MatchKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchKind::Exact => "exact",
+            MatchKind::Alias => "alias",
+            MatchKind::Prefix => "prefix",
+            MatchKind::NoMatch => "no-match",
+        }
+    }
+}
+
 pub struct AliasMatch {
     pub source_name: String,
     pub source_model_name: String,
@@ -27,23 +42,35 @@ pub struct AliasMatch {
 }
 
 pub struct AliasMap {
-    /// source_name → canonical_name (also used for prefix matching)
+    /// source_name → canonical_name
     to_canonical: HashMap<String, String>,
+    /// The same keys as `to_canonical`, indexed for prefix matching
+    prefixes: PrefixIndex,
+    /// canonical_name → family name
+    family_of: HashMap<String, String>,
 }
 
 impl AliasMap {
+    fn empty() -> Self {
+        Self {
+            to_canonical: HashMap::new(),
+            prefixes: PrefixIndex::default(),
+            family_of: HashMap::new(),
+        }
+    }
+
     pub fn load(override_path: Option<&str>) -> Result<Self> {
-        let mut to_canonical = HashMap::new();
+        let mut map = Self::empty();
 
         // Load bundled aliases
-        Self::parse_into(BUNDLED_ALIASES, &mut to_canonical)?;
+        map.parse_into(BUNDLED_ALIASES)?;
 
         // Load user override if it exists
         if let Some(path) = override_path {
             let p = PathBuf::from(path);
             if p.exists() {
                 let content = std::fs::read_to_string(&p)?;
-                Self::parse_into(&content, &mut to_canonical)?;
+                map.parse_into(&content)?;
             }
         } else {
             // Check default user override location
@@ -53,34 +80,49 @@ impl AliasMap {
                 .join("models.toml");
             if default_override.exists() {
                 let content = std::fs::read_to_string(&default_override)?;
-                Self::parse_into(&content, &mut to_canonical)?;
+                map.parse_into(&content)?;
             }
         }
 
-        Ok(Self { to_canonical })
+        Ok(map)
     }
 
+    /// Only the bundled models.toml, without any user override, so tests
+    /// don't depend on the machine they run on.
     #[cfg(test)]
-    fn from_toml(toml_str: &str) -> Self {
-        let mut to_canonical = HashMap::new();
-        Self::parse_into(toml_str, &mut to_canonical).unwrap();
-        Self { to_canonical }
+    pub(crate) fn bundled() -> Self {
+        Self::from_toml(BUNDLED_ALIASES)
     }
 
-    fn parse_into(toml_str: &str, map: &mut HashMap<String, String>) -> Result<()> {
+    #[cfg(test)]
+    pub(crate) fn from_toml(toml_str: &str) -> Self {
+        let mut map = Self::empty();
+        map.parse_into(toml_str).unwrap();
+        map
+    }
+
+    fn parse_into(&mut self, toml_str: &str) -> Result<()> {
         let entries: HashMap<String, AliasEntry> = toml::from_str(toml_str)?;
         for (_, entry) in entries {
             let canonical = entry.canonical.to_lowercase();
             // Map canonical to itself
-            map.insert(canonical.clone(), canonical.clone());
+            self.insert(canonical.clone(), canonical.clone());
             // Map each alias to canonical
             for alias in &entry.aliases {
-                map.insert(alias.to_lowercase(), canonical.clone());
+                self.insert(alias.to_lowercase(), canonical.clone());
+            }
+            if let Some(family) = entry.family {
+                self.family_of.insert(canonical, family.to_lowercase());
             }
         }
         Ok(())
     }
 
+    fn insert(&mut self, alias: String, canonical: String) {
+        self.prefixes.insert(&alias, &canonical);
+        self.to_canonical.insert(alias, canonical);
+    }
+
     /// Resolve a user-provided model name to its canonical form.
     /// Returns the input lowercased if no alias match found.
     pub fn resolve(&self, name: &str) -> String {
@@ -107,6 +149,92 @@ impl AliasMap {
         (lower, MatchKind::NoMatch)
     }
 
+    /// Resolve a source row to its canonical name, trying the normalised model
+    /// name first and falling back to the raw source model name.
+    pub fn canonical_for(&self, score: &ModelScore) -> String {
+        let by_model = self.resolve(&score.model);
+        if by_model != score.model.to_lowercase() {
+            return by_model;
+        }
+
+        let by_source_name = self.resolve(&score.source_model_name);
+        if by_source_name != score.source_model_name.to_lowercase() {
+            return by_source_name;
+        }
+
+        score.model.to_lowercase()
+    }
+
+    /// How a source row matched: by its raw source model name, else by its
+    /// normalised model name.
+    pub fn match_kind_for(&self, score: &ModelScore) -> MatchKind {
+        match self.resolve_with_kind(&score.source_model_name).1 {
+            MatchKind::NoMatch => self.resolve_with_kind(&score.model).1,
+            kind => kind,
+        }
+    }
+
+    /// Family a canonical model belongs to, if the alias map declares one.
+    pub fn family_of(&self, canonical: &str) -> Option<&str> {
+        self.family_of.get(canonical).map(String::as_str)
+    }
+
+    /// Members of a family ordered oldest → newest. Empty if `family` is unknown.
+    pub fn family_members(&self, family: &str) -> Vec<String> {
+        let family = family.to_lowercase();
+        let mut members: Vec<String> = self
+            .family_of
+            .iter()
+            .filter(|(_, f)| **f == family)
+            .map(|(canonical, _)| canonical.clone())
+            .collect();
+        members.sort_by(|a, b| version_key(a).cmp(&version_key(b)).then_with(|| a.cmp(b)));
+        members
+    }
+
+    /// Members of the family `name` refers to, oldest → newest. Empty when
+    /// `name` isn't a family, or is also a model name or alias, since a model
+    /// name always means that model.
+    pub fn expand_family(&self, name: &str) -> Vec<String> {
+        let name = name.to_lowercase();
+        if self.to_canonical.contains_key(&name) {
+            return Vec::new();
+        }
+        self.family_members(&name)
+    }
+
+    /// Canonical names that have a newer family member in `present`.
+    /// Models without a family are never superseded.
+    pub fn superseded(&self, present: &HashSet<String>) -> HashSet<String> {
+        let mut newest: HashMap<&str, &str> = HashMap::new();
+        for canonical in present {
+            let Some(family) = self.family_of(canonical) else {
+                continue;
+            };
+            match newest.get(family) {
+                Some(current) if version_key(current) >= version_key(canonical) => {}
+                _ => {
+                    newest.insert(family, canonical);
+                }
+            }
+        }
+
+        present
+            .iter()
+            .filter(|canonical| {
+                self.family_of(canonical)
+                    .and_then(|family| newest.get(family))
+                    .is_some_and(|latest| latest != canonical)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Whether `name` is a canonical name or alias, without prefix matching.
+    pub fn is_known(&self, name: &str) -> bool {
+        self.to_canonical.contains_key(&name.to_lowercase())
+    }
+
     /// Check if a source-specific model name matches a canonical name.
     pub fn matches(&self, source_name: &str, canonical: &str) -> bool {
         self.resolve(source_name) == canonical.to_lowercase()
@@ -135,35 +263,30 @@ impl AliasMap {
     ///   `(` or ` ` — parenthetical qualifier, e.g. `claude-opus-4.5 (reasoning)` → ok
     ///   `-` followed by a digit — date/version suffix, e.g. `gpt-5.2-2025-04-16` → ok
     ///   `-(` — effort/thinking qualifier, e.g. `claude-opus-4.6-(max)` → ok
+    ///   a final `-max` — max-effort variant, e.g. `claude-opus-4.6-max` → ok
     ///   `-` followed by a letter — model variant, e.g. `o3-pro`, `o3-mini` → NOT ok
     ///
     /// The letter-after-hyphen rule prevents short names like `o3` from swallowing
     /// genuinely distinct models (`o3-pro`, `o3-mini`). Add explicit aliases in
     /// models.toml for deployment-name patterns like `gpt-5.2-chat-latest`.
     fn prefix_match(&self, lower_name: &str) -> Option<String> {
+        self.prefixes.longest_match(lower_name).map(str::to_string)
+    }
+
+    /// Reference implementation of [`Self::prefix_match`]: a linear scan over
+    /// every alias, kept to check the index against.
+    #[cfg(test)]
+    fn prefix_match_scan(&self, lower_name: &str) -> Option<String> {
         let mut best: Option<(usize, String)> = None;
 
         for (alias, canonical) in &self.to_canonical {
-            if lower_name.len() > alias.len() && lower_name.starts_with(alias.as_str()) {
-                let next_char = lower_name.as_bytes()[alias.len()];
-                let allowed = match next_char {
-                    b'(' | b' ' => true,
-                    b'-' => {
-                        // Allow date/version suffixes and effort-style qualifiers
-                        // like "-(max)", but still reject model variants like
-                        // "-pro" or "-mini".
-                        lower_name
-                            .as_bytes()
-                            .get(alias.len() + 1)
-                            .is_some_and(|c| c.is_ascii_digit() || *c == b'(')
-                    }
-                    _ => false,
-                };
-                if allowed {
-                    let len = alias.len();
-                    if best.as_ref().is_none_or(|(best_len, _)| len > *best_len) {
-                        best = Some((len, canonical.clone()));
-                    }
+            if lower_name.len() > alias.len()
+                && lower_name.starts_with(alias.as_str())
+                && is_qualifier_suffix(&lower_name.as_bytes()[alias.len()..])
+            {
+                let len = alias.len();
+                if best.as_ref().is_none_or(|(best_len, _)| len > *best_len) {
+                    best = Some((len, canonical.clone()));
                 }
             }
         }
@@ -172,6 +295,96 @@ impl AliasMap {
     }
 }
 
+/// Whether `rest`, the part of a name after a known alias, starts like a
+/// qualifier rather than a different model (see [`AliasMap::prefix_match`]).
+fn is_qualifier_suffix(rest: &[u8]) -> bool {
+    match rest.first() {
+        Some(b'(' | b' ') => true,
+        // Allow date/version suffixes and effort-style qualifiers like "-(max)"
+        // or a final "-max", but still reject model variants like "-pro" or "-mini".
+        Some(b'-') => {
+            rest == b"-max"
+                || rest
+                    .get(1)
+                    .is_some_and(|c| c.is_ascii_digit() || *c == b'(')
+        }
+        _ => false,
+    }
+}
+
+/// Byte trie over lowercased aliases. A lookup walks the query once and
+/// remembers the deepest alias that ends on a qualifier boundary, so prefix
+/// resolution costs O(name length) instead of a scan over every alias.
+#[derive(Default)]
+struct PrefixIndex {
+    nodes: Vec<TrieNode>,
+}
+
+#[derive(Default)]
+struct TrieNode {
+    /// Sorted by byte for binary search
+    children: Vec<(u8, usize)>,
+    /// Canonical name when an alias ends at this node
+    canonical: Option<String>,
+}
+
+impl PrefixIndex {
+    fn insert(&mut self, alias: &str, canonical: &str) {
+        if self.nodes.is_empty() {
+            self.nodes.push(TrieNode::default());
+        }
+
+        let mut node = 0;
+        for &byte in alias.as_bytes() {
+            node = match self.nodes[node]
+                .children
+                .binary_search_by_key(&byte, |(b, _)| *b)
+            {
+                Ok(i) => self.nodes[node].children[i].1,
+                Err(i) => {
+                    let child = self.nodes.len();
+                    self.nodes.push(TrieNode::default());
+                    self.nodes[node].children.insert(i, (byte, child));
+                    child
+                }
+            };
+        }
+        self.nodes[node].canonical = Some(canonical.to_string());
+    }
+
+    /// Canonical name of the longest alias that is a strict prefix of `name`
+    /// followed by a qualifier suffix.
+    fn longest_match(&self, name: &str) -> Option<&str> {
+        let bytes = name.as_bytes();
+        let mut best = None;
+        let mut node = 0;
+
+        for (depth, byte) in bytes.iter().enumerate() {
+            let current = self.nodes.get(node)?;
+            if let Some(canonical) = &current.canonical
+                && is_qualifier_suffix(&bytes[depth..])
+            {
+                best = Some(canonical.as_str());
+            }
+            match current.children.binary_search_by_key(byte, |(b, _)| *b) {
+                Ok(i) => node = current.children[i].1,
+                Err(_) => break,
+            }
+        }
+
+        best
+    }
+}
+
+/// Numeric components of a model name, used to order releases within a family:
+/// `claude-sonnet-3.5` → `[3, 5]`, `gemini-3.1-pro` → `[3, 1]`.
+fn version_key(name: &str) -> Vec<u64> {
+    name.split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -256,6 +469,30 @@ aliases = ["claude-opus-4-6"]
         );
     }
 
+    #[test]
+    fn trailing_max_suffix_resolves_unless_it_is_a_model() {
+        let map = AliasMap::from_toml(
+            r#"
+[claude_opus_46]
+canonical = "claude-opus-4.6"
+
+[qwen-3]
+canonical = "qwen-3"
+aliases = ["Qwen3"]
+
+[qwen-3-max]
+canonical = "qwen-3-max"
+aliases = ["qwen3-max"]
+"#,
+        );
+        assert_eq!(map.resolve("claude-opus-4.6-max"), "claude-opus-4.6");
+        assert_eq!(map.resolve("qwen3-max"), "qwen-3-max");
+        assert_eq!(
+            map.resolve("claude-opus-4.6-maximal"),
+            "claude-opus-4.6-maximal"
+        );
+    }
+
     // --- Hyphen + letter: NOT allowed (model variant) ---
 
     #[test]
@@ -287,4 +524,184 @@ aliases = ["claude-opus-4-6"]
     fn completely_unknown_returns_itself() {
         assert_eq!(map().resolve("unknown-model-xyz"), "unknown-model-xyz");
     }
+
+    // --- Families ---
+
+    const FAMILY_TOML: &str = r#"
+[sonnet-3_5]
+canonical = "claude-sonnet-3.5"
+family = "claude-sonnet"
+
+[sonnet-4_6]
+canonical = "claude-sonnet-4.6"
+family = "claude-sonnet"
+
+[sonnet-4_5]
+canonical = "claude-sonnet-4.5"
+family = "claude-sonnet"
+
+[gpt-4o]
+canonical = "gpt-4o"
+"#;
+
+    #[test]
+    fn family_members_ordered_by_version() {
+        assert_eq!(
+            AliasMap::from_toml(FAMILY_TOML).family_members("claude-sonnet"),
+            vec![
+                "claude-sonnet-3.5",
+                "claude-sonnet-4.5",
+                "claude-sonnet-4.6"
+            ]
+        );
+    }
+
+    #[test]
+    fn model_names_are_not_expanded_as_families() {
+        let map = AliasMap::from_toml(
+            r#"
+[gpt-5]
+canonical = "gpt-5"
+family = "gpt-5"
+
+[gpt-5_2]
+canonical = "gpt-5.2"
+family = "gpt-5"
+"#,
+        );
+        assert!(map.expand_family("gpt-5").is_empty());
+        assert_eq!(
+            AliasMap::from_toml(FAMILY_TOML).expand_family("Claude-Sonnet"),
+            vec![
+                "claude-sonnet-3.5",
+                "claude-sonnet-4.5",
+                "claude-sonnet-4.6"
+            ]
+        );
+
+        // The bundled family is named so it can't collide with GPT-5 itself
+        let bundled = AliasMap::bundled();
+        assert!(bundled.expand_family("gpt-5").is_empty());
+        assert_eq!(
+            bundled.expand_family("gpt-5-series"),
+            vec!["gpt-5", "gpt-5.1", "gpt-5.2", "gpt-5.4"]
+        );
+    }
+
+    #[test]
+    fn gpt5_submodels_do_not_resolve_to_gpt5() {
+        let bundled = AliasMap::bundled();
+        assert_ne!(bundled.resolve("GPT-5 mini"), "gpt-5");
+        assert_eq!(bundled.resolve("GPT-5 mini"), "gpt-5-mini");
+        assert_eq!(bundled.resolve("GPT-5 nano (high)"), "gpt-5-nano");
+        assert_eq!(bundled.resolve("GPT-5 Codex"), "gpt-5-codex");
+        assert_eq!(bundled.resolve("GPT-5 (high)"), "gpt-5");
+    }
+
+    #[test]
+    fn superseded_keeps_newest_present_member() {
+        let map = AliasMap::from_toml(FAMILY_TOML);
+        // 4.6 is not benchmarked anywhere yet → 4.5 is the newest present
+        let present: HashSet<String> = ["claude-sonnet-3.5", "claude-sonnet-4.5", "gpt-4o"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let superseded = map.superseded(&present);
+        assert_eq!(superseded, HashSet::from(["claude-sonnet-3.5".to_string()]));
+    }
+
+    // --- Prefix index ---
+
+    /// ~5k aliases with heavy prefix overlap: `m12`, `m12-pro`, `m12-pro-2`,
+    /// `vendor/m12.5`, `Model 12 Ultra`.
+    fn synthetic_map() -> AliasMap {
+        let mut map = AliasMap::empty();
+        for i in 0..1000 {
+            let canonical = format!("m{i}");
+            for alias in [
+                format!("m{i}"),
+                format!("m{i}-pro"),
+                format!("m{i}-pro-2"),
+                format!("vendor/m{i}.5"),
+                format!("model {i} ultra"),
+            ] {
+                map.insert(alias, canonical.clone());
+            }
+        }
+        map
+    }
+
+    fn synthetic_queries() -> Vec<String> {
+        let suffixes = [
+            "",
+            " (high)",
+            "(xhigh)",
+            "-(max)",
+            "-2025-01-31",
+            "-mini",
+            "-pro-3",
+            "x",
+            " ",
+        ];
+        (0..1000)
+            .step_by(7)
+            .flat_map(|i| {
+                [
+                    format!("m{i}"),
+                    format!("m{i}-pro"),
+                    format!("vendor/m{i}.5"),
+                    format!("model {i} ultra"),
+                    format!("m{i}0"),
+                ]
+            })
+            .flat_map(|base| suffixes.iter().map(move |suffix| format!("{base}{suffix}")))
+            .collect()
+    }
+
+    #[test]
+    fn prefix_index_matches_linear_scan() {
+        let map = synthetic_map();
+        assert_eq!(map.to_canonical.len(), 5000);
+
+        for query in synthetic_queries() {
+            assert_eq!(
+                map.prefix_match(&query),
+                map.prefix_match_scan(&query),
+                "query {query:?}"
+            );
+        }
+    }
+
+    /// Wall-clock benchmark, flaky on loaded machines; run with `--ignored`.
+    /// `prefix_index_matches_linear_scan` covers correctness.
+    #[test]
+    #[ignore]
+    fn prefix_index_is_faster_than_linear_scan() {
+        use std::time::Instant;
+
+        let map = synthetic_map();
+        let queries = synthetic_queries();
+
+        let start = Instant::now();
+        let indexed: usize = queries
+            .iter()
+            .filter(|q| map.prefix_match(q).is_some())
+            .count();
+        let indexed_time = start.elapsed();
+
+        let start = Instant::now();
+        let scanned: usize = queries
+            .iter()
+            .filter(|q| map.prefix_match_scan(q).is_some())
+            .count();
+        let scan_time = start.elapsed();
+
+        assert_eq!(indexed, scanned);
+        // Loose bound: the index should win by a wide margin even in debug builds
+        assert!(
+            indexed_time * 10 < scan_time,
+            "indexed {indexed_time:?} vs scan {scan_time:?} over {} queries",
+            queries.len()
+        );
+    }
 }
@@ -0,0 +1,270 @@
+use crate::aggregate::{AggregateOptions, ResolvedSource, combined_scores};
+use crate::ranking::competition_ranks;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy)]
+pub struct BootstrapOptions {
+    pub replicates: usize,
+    pub seed: u64,
+    pub top_k: usize,
+}
+
+/// Where a model lands across bootstrap replicates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankInterval {
+    /// 2.5th percentile of the model's rank (best case)
+    pub lo: u32,
+    /// 97.5th percentile of the model's rank (worst case)
+    pub hi: u32,
+    /// Share of replicates in which the model ranked within the top k
+    pub p_top_k: f64,
+}
+
+/// Re-run the aggregate on resampled data and report a 95% rank interval and a
+/// top-k probability for each of `models`.
+///
+/// Each replicate draws as many sources as there are, with replacement, and
+/// jitters every value that has a published standard error (Arena's ±CI) by a
+/// normal draw of that size before re-ranking the source. Models are ranked
+/// against each other only; a model none of the drawn sources lists ranks last.
+pub fn bootstrap_ranks(
+    sources: &[ResolvedSource],
+    models: &[String],
+    options: &AggregateOptions,
+    bootstrap: &BootstrapOptions,
+) -> HashMap<String, RankInterval> {
+    if sources.is_empty() || models.is_empty() || bootstrap.replicates == 0 {
+        return HashMap::new();
+    }
+
+    let mut rng = SplitMix64::new(bootstrap.seed);
+    let mut ranks: HashMap<&str, Vec<u32>> = models
+        .iter()
+        .map(|model| (model.as_str(), Vec::with_capacity(bootstrap.replicates)))
+        .collect();
+
+    for _ in 0..bootstrap.replicates {
+        let replicate: Vec<ResolvedSource> = (0..sources.len())
+            .map(|_| perturb(&sources[rng.below(sources.len())], &mut rng))
+            .collect();
+        let scores = combined_scores(&replicate, options);
+
+        let mut order: Vec<&String> = models.iter().collect();
+        order.sort_by(|a, b| {
+            let score_a = scores.get(*a).copied().unwrap_or(f64::NEG_INFINITY);
+            let score_b = scores.get(*b).copied().unwrap_or(f64::NEG_INFINITY);
+            score_b
+                .partial_cmp(&score_a)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.cmp(b))
+        });
+        for (i, model) in order.into_iter().enumerate() {
+            if let Some(model_ranks) = ranks.get_mut(model.as_str()) {
+                model_ranks.push((i + 1) as u32);
+            }
+        }
+    }
+
+    ranks
+        .into_iter()
+        .map(|(model, mut model_ranks)| {
+            model_ranks.sort_unstable();
+            let n = model_ranks.len();
+            let lo = model_ranks[((n - 1) as f64 * 0.025).floor() as usize];
+            let hi = model_ranks[((n - 1) as f64 * 0.975).ceil() as usize];
+            let in_top_k = model_ranks
+                .iter()
+                .filter(|rank| (**rank as usize) <= bootstrap.top_k)
+                .count();
+            (
+                model.to_string(),
+                RankInterval {
+                    lo,
+                    hi,
+                    p_top_k: in_top_k as f64 / n as f64,
+                },
+            )
+        })
+        .collect()
+}
+
+/// A copy of `source` with each value jittered by its standard error and the
+/// rows re-ranked. Sources without standard errors are returned unchanged.
+fn perturb(source: &ResolvedSource, rng: &mut SplitMix64) -> ResolvedSource {
+    let mut source = source.clone();
+    if source.rows.iter().all(|row| row.std_error.is_none()) {
+        return source;
+    }
+
+    for row in &mut source.rows {
+        if let Some(std_error) = row.std_error {
+            row.value += rng.normal() * std_error;
+        }
+    }
+    source
+        .rows
+        .sort_by(|a, b| b.value.partial_cmp(&a.value).unwrap_or(Ordering::Equal));
+    let ranks = competition_ranks(source.rows.iter().map(|row| row.value));
+    for (row, rank) in source.rows.iter_mut().zip(ranks) {
+        row.rank = rank;
+    }
+    source
+}
+
+/// SplitMix64 (Steele, Lea & Flood): small, fast and good enough for
+/// resampling. Seeded so that runs are reproducible.
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in `0..n`.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_f64() * n as f64) as usize
+    }
+
+    /// Standard normal via Box–Muller.
+    fn normal(&mut self) -> f64 {
+        let u1 = 1.0 - self.next_f64();
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggregate::{AggregateMethod, Coverage, ResolvedRow};
+    use crate::variant::VariantPolicy;
+
+    fn options() -> AggregateOptions {
+        AggregateOptions {
+            min_sources: 1,
+            show_excluded: false,
+            variant: VariantPolicy::Best,
+            method: AggregateMethod::Percentile,
+            coverage: Coverage::None,
+            weights: None,
+        }
+    }
+
+    fn source(name: &str, rows: &[(&str, f64, Option<f64>)]) -> ResolvedSource {
+        ResolvedSource {
+            source: name.to_string(),
+            metric: Some("score"),
+            rows: rows
+                .iter()
+                .enumerate()
+                .map(|(i, (model, value, std_error))| ResolvedRow {
+                    model: model.to_string(),
+                    source_model_name: model.to_string(),
+                    rank: (i + 1) as u32,
+                    value: *value,
+                    std_error: *std_error,
+                })
+                .collect(),
+        }
+    }
+
+    fn models(names: &[&str]) -> Vec<String> {
+        names.iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn unanimous_sources_give_tight_intervals() {
+        let sources = vec![
+            source("a", &[("x", 3.0, None), ("y", 2.0, None), ("z", 1.0, None)]),
+            source("b", &[("x", 3.0, None), ("y", 2.0, None), ("z", 1.0, None)]),
+        ];
+        let bootstrap = BootstrapOptions {
+            replicates: 200,
+            seed: 7,
+            top_k: 1,
+        };
+
+        let intervals =
+            bootstrap_ranks(&sources, &models(&["x", "y", "z"]), &options(), &bootstrap);
+        assert_eq!(
+            intervals["x"],
+            RankInterval {
+                lo: 1,
+                hi: 1,
+                p_top_k: 1.0
+            }
+        );
+        assert_eq!((intervals["z"].lo, intervals["z"].hi), (3, 3));
+    }
+
+    #[test]
+    fn disagreeing_sources_widen_intervals() {
+        let sources = vec![
+            source("a", &[("x", 2.0, None), ("y", 1.0, None)]),
+            source("b", &[("y", 2.0, None), ("x", 1.0, None)]),
+            source("c", &[("x", 2.0, None), ("y", 1.0, None)]),
+        ];
+        let bootstrap = BootstrapOptions {
+            replicates: 500,
+            seed: 1,
+            top_k: 1,
+        };
+
+        let intervals = bootstrap_ranks(&sources, &models(&["x", "y"]), &options(), &bootstrap);
+        assert_eq!((intervals["x"].lo, intervals["x"].hi), (1, 2));
+        // x leads in 2 of 3 sources, so it wins most (but not all) replicates
+        assert!(intervals["x"].p_top_k > 0.6 && intervals["x"].p_top_k < 0.95);
+        assert!((intervals["x"].p_top_k + intervals["y"].p_top_k - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn standard_errors_let_close_values_swap() {
+        // One source, x barely ahead of y with overlapping intervals
+        let sources = vec![source(
+            "arena",
+            &[("x", 1501.0, Some(5.0)), ("y", 1500.0, Some(5.0))],
+        )];
+        let bootstrap = BootstrapOptions {
+            replicates: 500,
+            seed: 3,
+            top_k: 1,
+        };
+
+        let intervals = bootstrap_ranks(&sources, &models(&["x", "y"]), &options(), &bootstrap);
+        assert_eq!((intervals["y"].lo, intervals["y"].hi), (1, 2));
+        assert!(intervals["x"].p_top_k > 0.4 && intervals["x"].p_top_k < 0.7);
+    }
+
+    #[test]
+    fn same_seed_is_reproducible() {
+        let sources = vec![
+            source("a", &[("x", 2.0, Some(1.0)), ("y", 1.5, Some(1.0))]),
+            source("b", &[("y", 2.0, None), ("x", 1.0, None)]),
+        ];
+        let bootstrap = BootstrapOptions {
+            replicates: 100,
+            seed: 42,
+            top_k: 1,
+        };
+
+        let first = bootstrap_ranks(&sources, &models(&["x", "y"]), &options(), &bootstrap);
+        let second = bootstrap_ranks(&sources, &models(&["x", "y"]), &options(), &bootstrap);
+        assert_eq!(first, second);
+    }
+}
@@ -0,0 +1,619 @@
+use crate::aggregate::{ResolvedRow, ResolvedSource, resolve_sources};
+use crate::alias::AliasMap;
+use crate::models::{QueryInfo, SourceResult};
+use crate::output::{OutputFormat, padded_row};
+use crate::variant::VariantPolicy;
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// z for a two-sided 95% interval on the difference of two independent means.
+const NOISE_Z: f64 = 1.96;
+
+/// How a source decides between the two models, from the first model's side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    Win,
+    Loss,
+    /// Equal values, or a difference within the source's published uncertainty
+    Tie,
+}
+
+impl Verdict {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Win => "win",
+            Self::Loss => "loss",
+            Self::Tie => "tie",
+        }
+    }
+}
+
+/// One model's row in a source.
+#[derive(Debug, Clone, Serialize)]
+pub struct Reading {
+    pub source_model_name: String,
+    pub rank: u32,
+    /// The primary metric, or the negated rank for sources without one
+    pub value: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub std_error: Option<f64>,
+}
+
+impl From<&ResolvedRow> for Reading {
+    fn from(row: &ResolvedRow) -> Self {
+        Self {
+            source_model_name: row.source_model_name.clone(),
+            rank: row.rank,
+            value: row.value,
+            std_error: row.std_error,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SourceVerdict {
+    pub source: String,
+    /// The compared metric; `rank` when the source has none
+    pub metric: String,
+    pub first: Option<Reading>,
+    pub second: Option<Reading>,
+    /// `first − second` in the metric's units, positive when the first model
+    /// is ahead. For rank-only sources this is the gap in places.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta: Option<f64>,
+    /// `delta` as a share of the second model's value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relative: Option<f64>,
+    /// Smallest |delta| that isn't a tie, from the sources' standard errors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub noise: Option<f64>,
+    /// Absent unless the source lists both models
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verdict: Option<Verdict>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompareSummary {
+    /// Sources the first model wins
+    pub wins: usize,
+    /// Sources the second model wins
+    pub losses: usize,
+    pub ties: usize,
+    /// Sources that list only one of the two models
+    pub unshared: usize,
+    /// The model that wins more sources; absent when they're level
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leader: Option<String>,
+    pub line: String,
+}
+
+/// Two models judged in every source that lists either of them.
+#[derive(Debug, Serialize)]
+pub struct HeadToHead {
+    pub models: [String; 2],
+    pub sources: Vec<SourceVerdict>,
+    pub summary: CompareSummary,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PivotSource {
+    pub source: String,
+    /// The compared metric; `rank` when the source has none
+    pub metric: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PivotRow {
+    pub model: String,
+    /// Keyed by source; sources that don't list the model are left out
+    pub cells: BTreeMap<String, Reading>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompareOutput {
+    pub timestamp: DateTime<Utc>,
+    pub query: QueryInfo,
+    /// Every source with its rows for the compared models, variants included,
+    /// as `compare` printed before verdicts were added
+    pub sources: Vec<SourceResult>,
+    /// Already in `query.models`
+    #[serde(skip)]
+    pub models: Vec<String>,
+    /// Sources listing at least one of the models, in fetch order
+    pub columns: Vec<PivotSource>,
+    pub pivot: Vec<PivotRow>,
+    /// `wins[i][j]` is the number of sources where `models[i]` beats `models[j]`
+    pub wins: Vec<Vec<usize>>,
+    /// Every pair of models, in the order given
+    pub pairs: Vec<HeadToHead>,
+}
+
+/// Compare canonical `models` across every source, using each source's best
+/// row per model: one pivot row per model and a head-to-head for every pair.
+pub fn compare(
+    results: &[SourceResult],
+    aliases: &AliasMap,
+    models: &[String],
+) -> Result<CompareOutput> {
+    for (index, model) in models.iter().enumerate() {
+        if models[..index].contains(model) {
+            bail!("{model} is named more than once (after alias resolution)");
+        }
+    }
+    if models.len() < 2 {
+        bail!("compare needs at least two models");
+    }
+
+    let resolved: Vec<ResolvedSource> = resolve_sources(results, aliases, VariantPolicy::Best)
+        .into_iter()
+        .filter(|source| source.rows.iter().any(|row| models.contains(&row.model)))
+        .collect();
+
+    let sources = results
+        .iter()
+        .map(|result| SourceResult {
+            scores: result
+                .scores
+                .iter()
+                .filter(|score| models.contains(&aliases.canonical_for(score)))
+                .cloned()
+                .collect(),
+            ..result.clone()
+        })
+        .collect();
+    let columns = resolved
+        .iter()
+        .map(|source| PivotSource {
+            source: source.source.clone(),
+            metric: source.metric.unwrap_or("rank").to_string(),
+        })
+        .collect();
+    let pivot = models
+        .iter()
+        .map(|model| PivotRow {
+            model: model.clone(),
+            cells: resolved
+                .iter()
+                .filter_map(|source| {
+                    let row = source.rows.iter().find(|row| &row.model == model)?;
+                    Some((source.source.clone(), Reading::from(row)))
+                })
+                .collect(),
+        })
+        .collect();
+
+    let mut wins = vec![vec![0; models.len()]; models.len()];
+    let mut pairs = Vec::new();
+    for i in 0..models.len() {
+        for j in i + 1..models.len() {
+            let pair = head_to_head(&resolved, &models[i], &models[j]);
+            wins[i][j] = pair.summary.wins;
+            wins[j][i] = pair.summary.losses;
+            pairs.push(pair);
+        }
+    }
+
+    Ok(CompareOutput {
+        timestamp: Utc::now(),
+        query: QueryInfo {
+            query_type: "compare".into(),
+            model: None,
+            models: Some(models.to_vec()),
+            top: None,
+        },
+        sources,
+        models: models.to_vec(),
+        columns,
+        pivot,
+        wins,
+        pairs,
+    })
+}
+
+/// Judge `first` against `second` in every source that lists either.
+pub fn head_to_head(resolved: &[ResolvedSource], first: &str, second: &str) -> HeadToHead {
+    let mut sources = Vec::new();
+    for source in resolved {
+        let find = |model: &str| source.rows.iter().find(|row| row.model == model);
+        let (a, b) = (find(first), find(second));
+        if a.is_none() && b.is_none() {
+            continue;
+        }
+        let mut verdict = SourceVerdict {
+            source: source.source.clone(),
+            metric: source.metric.unwrap_or("rank").to_string(),
+            first: a.map(Reading::from),
+            second: b.map(Reading::from),
+            delta: None,
+            relative: None,
+            noise: None,
+            verdict: None,
+        };
+        if let (Some(a), Some(b)) = (a, b) {
+            let (delta, noise, outcome) = judge(a, b);
+            verdict.delta = Some(delta);
+            verdict.relative = source
+                .metric
+                .filter(|_| b.value != 0.0)
+                .map(|_| delta / b.value.abs());
+            verdict.noise = noise;
+            verdict.verdict = Some(outcome);
+        }
+        sources.push(verdict);
+    }
+
+    let summary = summarize(first, second, &sources);
+    HeadToHead {
+        models: [first.to_string(), second.to_string()],
+        sources,
+        summary,
+    }
+}
+
+/// `a.value − b.value`, the noise threshold when either row has a standard
+/// error, and the verdict for `a`.
+pub fn judge(a: &ResolvedRow, b: &ResolvedRow) -> (f64, Option<f64>, Verdict) {
+    let delta = a.value - b.value;
+    let noise = (a.std_error.is_some() || b.std_error.is_some()).then(|| {
+        let variance = a.std_error.unwrap_or(0.0).powi(2) + b.std_error.unwrap_or(0.0).powi(2);
+        NOISE_Z * variance.sqrt()
+    });
+    let verdict = if delta == 0.0 || noise.is_some_and(|noise| delta.abs() <= noise) {
+        Verdict::Tie
+    } else if delta > 0.0 {
+        Verdict::Win
+    } else {
+        Verdict::Loss
+    };
+    (delta, noise, verdict)
+}
+
+fn summarize(first: &str, second: &str, sources: &[SourceVerdict]) -> CompareSummary {
+    let count = |wanted: Verdict| {
+        sources
+            .iter()
+            .filter(|source| source.verdict == Some(wanted))
+            .count()
+    };
+    let (wins, losses, ties) = (
+        count(Verdict::Win),
+        count(Verdict::Loss),
+        count(Verdict::Tie),
+    );
+    let shared = wins + losses + ties;
+    let unshared = sources.len() - shared;
+
+    let leader = match wins.cmp(&losses) {
+        std::cmp::Ordering::Greater => Some(first),
+        std::cmp::Ordering::Less => Some(second),
+        std::cmp::Ordering::Equal => None,
+    };
+    let mut line = if shared == 0 {
+        format!("No source lists both {first} and {second}")
+    } else {
+        let tally = match leader {
+            Some(leader) if leader == first => format!("{first} leads {second}"),
+            Some(_) => format!("{second} leads {first}"),
+            None => format!("{first} and {second} are level"),
+        };
+        let (ahead, behind) = (wins.max(losses), wins.min(losses));
+        format!("{tally} {ahead}–{behind} with {ties} tie(s) across {shared} shared source(s)")
+    };
+    if unshared > 0 {
+        line.push_str(&format!("; {unshared} source(s) list only one"));
+    }
+
+    CompareSummary {
+        wins,
+        losses,
+        ties,
+        unshared,
+        leader: leader.map(str::to_string),
+        line,
+    }
+}
+
+pub fn render(output: &CompareOutput, format: OutputFormat) -> Result<String> {
+    Ok(match format {
+        OutputFormat::Json => serde_json::to_string_pretty(output)?,
+        OutputFormat::Table => render_text(output, false),
+        OutputFormat::Markdown => render_text(output, true),
+    })
+}
+
+fn render_text(output: &CompareOutput, markdown: bool) -> String {
+    let heading = |text: String| {
+        if markdown {
+            format!("### {text}")
+        } else {
+            text
+        }
+    };
+
+    // A single pair needs nothing beyond its head-to-head
+    if let [pair] = output.pairs.as_slice() {
+        let [first, second] = &pair.models;
+        let mut lines = vec![heading(format!("{first} vs {second}")), String::new()];
+        let headers = [
+            "Source", "Metric", first, second, "Delta", "Rel.", "Noise", "Verdict",
+        ];
+        let rows: Vec<Vec<String>> = pair.sources.iter().map(verdict_cells).collect();
+        lines.extend(table(&headers, &rows, markdown));
+        lines.push(String::new());
+        lines.push(pair.summary.line.clone());
+        return lines.join("\n");
+    }
+
+    let mut lines = vec![heading("Scores by source".to_string()), String::new()];
+    let mut headers = vec!["Model".to_string()];
+    headers.extend(
+        output
+            .columns
+            .iter()
+            .map(|source| format!("{} ({})", source.source, source.metric)),
+    );
+    let rows: Vec<Vec<String>> = output
+        .pivot
+        .iter()
+        .map(|row| {
+            let mut cells = vec![row.model.clone()];
+            cells.extend(
+                output
+                    .columns
+                    .iter()
+                    .map(|source| reading_cell(&source.metric, row.cells.get(&source.source))),
+            );
+            cells
+        })
+        .collect();
+    let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+    lines.extend(table(&headers, &rows, markdown));
+
+    lines.push(String::new());
+    lines.push(heading(
+        "Sources won, row over column (ties and unshared sources not counted)".to_string(),
+    ));
+    lines.push(String::new());
+    let mut headers = vec!["Model"];
+    headers.extend(output.models.iter().map(String::as_str));
+    headers.push("Won–lost");
+    let rows: Vec<Vec<String>> = output
+        .models
+        .iter()
+        .enumerate()
+        .map(|(i, model)| {
+            let mut cells = vec![model.clone()];
+            cells.extend((0..output.models.len()).map(|j| {
+                if i == j {
+                    "—".to_string()
+                } else {
+                    output.wins[i][j].to_string()
+                }
+            }));
+            let won: usize = output.wins[i].iter().sum();
+            let lost: usize = output.wins.iter().map(|row| row[i]).sum();
+            cells.push(format!("{won}–{lost}"));
+            cells
+        })
+        .collect();
+    lines.extend(table(&headers, &rows, markdown));
+
+    lines.push(String::new());
+    let bullet = if markdown { "- " } else { "" };
+    for pair in &output.pairs {
+        lines.push(format!("{bullet}{}", pair.summary.line));
+    }
+    lines.join("\n")
+}
+
+fn reading_cell(metric: &str, reading: Option<&Reading>) -> String {
+    match reading {
+        Some(reading) if metric == "rank" => format!("#{}", reading.rank),
+        Some(reading) => format!("{:.2}", reading.value),
+        None => "—".to_string(),
+    }
+}
+
+fn verdict_cells(source: &SourceVerdict) -> Vec<String> {
+    let reading = |reading: &Option<Reading>| reading_cell(&source.metric, reading.as_ref());
+    let dash = || "—".to_string();
+    vec![
+        source.source.clone(),
+        source.metric.clone(),
+        reading(&source.first),
+        reading(&source.second),
+        source
+            .delta
+            .map_or_else(dash, |delta| format!("{delta:+.2}")),
+        source
+            .relative
+            .map_or_else(dash, |relative| format!("{:+.1}%", relative * 100.0)),
+        source
+            .noise
+            .map_or_else(dash, |noise| format!("±{noise:.2}")),
+        source.verdict.map_or_else(
+            || "not shared".to_string(),
+            |verdict| verdict.as_str().to_string(),
+        ),
+    ]
+}
+
+fn table(headers: &[&str], rows: &[Vec<String>], markdown: bool) -> Vec<String> {
+    let headers: Vec<String> = headers.iter().map(|header| header.to_string()).collect();
+    if markdown {
+        let mut lines = vec![
+            format!("| {} |", headers.join(" | ")),
+            format!("| {} |", vec!["---"; headers.len()].join(" | ")),
+        ];
+        for row in rows {
+            lines.push(format!("| {} |", row.join(" | ")));
+        }
+        return lines;
+    }
+
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+    let mut lines = vec![format!("  {}", padded_row(&headers, &widths))];
+    for row in rows {
+        lines.push(format!("  {}", padded_row(row, &widths)));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MetricValue, ModelScore, SourceStatus};
+    use crate::variant::parse_variant;
+    use std::collections::HashMap;
+
+    fn source(name: &str, rows: &[(&str, &[(&str, f64)])]) -> SourceResult {
+        SourceResult {
+            source: name.to_string(),
+            fetched_at: None,
+            status: SourceStatus::Ok,
+            scores: rows
+                .iter()
+                .enumerate()
+                .map(|(i, (model, metrics))| ModelScore {
+                    model: model.to_string(),
+                    source_model_name: model.to_string(),
+                    variant: parse_variant(model),
+                    agent: None,
+                    metrics: metrics
+                        .iter()
+                        .map(|(metric, value)| (metric.to_string(), MetricValue::Float(*value)))
+                        .collect::<HashMap<_, _>>(),
+                    rank: Some((i + 1) as u32),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn differences_within_published_uncertainty_are_ties() {
+        let aliases = AliasMap::bundled();
+        let results = vec![
+            // 1500 vs 1490 with ±12 CIs: inside noise
+            source(
+                "arena",
+                &[
+                    ("model-a", &[("elo_score", 1500.0), ("elo_ci", 12.0)]),
+                    ("model-b", &[("elo_score", 1490.0), ("elo_ci", 12.0)]),
+                ],
+            ),
+            // 70 vs 60 with a 1-point SEM: a clear win
+            source(
+                "swe-rebench",
+                &[
+                    (
+                        "model-a",
+                        &[("resolve_rate", 70.0), ("resolve_rate_sem", 1.0)],
+                    ),
+                    (
+                        "model-b",
+                        &[("resolve_rate", 60.0), ("resolve_rate_sem", 1.0)],
+                    ),
+                ],
+            ),
+            // No uncertainty published, so any gap counts
+            source(
+                "aider",
+                &[
+                    ("model-b", &[("pass_rate_1", 80.0)]),
+                    ("model-a", &[("pass_rate_1", 79.5)]),
+                ],
+            ),
+            source("livebench", &[("model-a", &[("global_average", 50.0)])]),
+        ];
+
+        let models = ["model-a".to_string(), "model-b".to_string()];
+        let output = compare(&results, &aliases, &models).unwrap();
+        let output = &output.pairs[0];
+        let verdict = |name: &str| {
+            output
+                .sources
+                .iter()
+                .find(|source| source.source == name)
+                .unwrap()
+        };
+
+        let arena = verdict("arena");
+        assert_eq!(arena.verdict, Some(Verdict::Tie));
+        assert!(
+            (arena.noise.unwrap() - 1.96 * (2.0 * (12.0f64 / 1.96).powi(2)).sqrt()).abs() < 1e-9
+        );
+
+        let rebench = verdict("swe-rebench");
+        assert_eq!(rebench.verdict, Some(Verdict::Win));
+        assert!((rebench.relative.unwrap() - 10.0 / 60.0).abs() < 1e-9);
+
+        assert_eq!(verdict("aider").verdict, Some(Verdict::Loss));
+        assert_eq!(verdict("livebench").verdict, None);
+
+        assert_eq!(
+            (
+                output.summary.wins,
+                output.summary.losses,
+                output.summary.ties
+            ),
+            (1, 1, 1)
+        );
+        assert_eq!(output.summary.unshared, 1);
+        assert_eq!(output.summary.leader, None);
+        assert_eq!(
+            output.summary.line,
+            "model-a and model-b are level 1–1 with 1 tie(s) across 3 shared source(s); \
+             1 source(s) list only one"
+        );
+    }
+
+    #[test]
+    fn win_matrix_counts_each_pair_once_per_source() {
+        let aliases = AliasMap::bundled();
+        let listing = |name: &str, order: &[&str]| {
+            let rows: Vec<(&str, &[(&str, f64)])> =
+                order.iter().map(|model| (*model, &[][..])).collect();
+            source(name, &rows)
+        };
+        // Rank-only sources: earlier in the list wins
+        let results = vec![
+            listing("source-1", &["model-a", "model-b", "model-c"]),
+            listing("source-2", &["model-b", "model-a", "model-c"]),
+            listing("source-3", &["model-a", "model-c", "other"]),
+        ];
+        let models: Vec<String> = ["model-a", "model-b", "model-c"]
+            .iter()
+            .map(|model| model.to_string())
+            .collect();
+        let output = compare(&results, &aliases, &models).unwrap();
+
+        assert_eq!(output.columns.len(), 3);
+        // The raw rows are kept, minus models that weren't asked for
+        assert!(
+            output.sources[2]
+                .scores
+                .iter()
+                .all(|score| score.model != "other")
+        );
+        assert_eq!(output.pivot[1].cells.len(), 2);
+        assert_eq!(output.pairs.len(), 3);
+        assert_eq!(
+            output.wins,
+            vec![vec![0, 1, 3], vec![1, 0, 2], vec![0, 0, 0]]
+        );
+
+        let table = render(&output, OutputFormat::Table).unwrap();
+        assert!(table.contains("Won–lost"), "{table}");
+
+        let duplicate = ["model-a".to_string(), "model-a".to_string()];
+        assert!(compare(&results, &aliases, &duplicate).is_err());
+    }
+}
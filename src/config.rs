@@ -1,4 +1,5 @@
-use anyhow::Result;
+use crate::recommend::SortDirection;
+use anyhow::{Result, bail};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -13,6 +14,14 @@ pub struct Config {
     pub cache: CacheConfig,
     #[serde(default)]
     pub alias: AliasConfig,
+    #[serde(default)]
+    pub aggregate: AggregateConfig,
+    #[serde(default)]
+    pub cost: CostConfig,
+    #[serde(default)]
+    pub recommend: RecommendConfig,
+    #[serde(default)]
+    pub roster: RosterConfig,
     #[serde(skip)]
     pub aa_api_key: Option<String>,
 }
@@ -46,6 +55,109 @@ pub struct AliasConfig {
     pub path: Option<String>,
 }
 
+#[derive(Debug, Deserialize, Default)]
+pub struct AggregateConfig {
+    /// Named source weightings for `rank --aggregate --profile <name>`
+    #[serde(default)]
+    pub profiles: HashMap<String, HashMap<String, f64>>,
+}
+
+/// Default token mix for `pondus cost` and `recommend cost`.
+#[derive(Debug, Deserialize)]
+pub struct CostConfig {
+    #[serde(default = "default_input_tokens")]
+    pub input_tokens: u64,
+    #[serde(default = "default_output_tokens")]
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub cached_ratio: f64,
+}
+
+impl Default for CostConfig {
+    fn default() -> Self {
+        Self {
+            input_tokens: default_input_tokens(),
+            output_tokens: default_output_tokens(),
+            cached_ratio: 0.0,
+        }
+    }
+}
+
+impl CostConfig {
+    /// Reject a `cached_ratio` outside 0–1, which would bill negative input.
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.cached_ratio) {
+            bail!(
+                "[cost] cached_ratio must be between 0 and 1, got {}",
+                self.cached_ratio
+            );
+        }
+        Ok(())
+    }
+}
+
+fn default_input_tokens() -> u64 {
+    3_000_000
+}
+
+fn default_output_tokens() -> u64 {
+    1_000_000
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct RecommendConfig {
+    /// Extra `recommend` tasks by name; a built-in name replaces that task
+    #[serde(default)]
+    pub tasks: HashMap<String, TaskConfig>,
+}
+
+/// Models a team runs in production, for `pondus advise`.
+#[derive(Debug, Deserialize)]
+pub struct RosterConfig {
+    /// Recommend task whose metrics an upgrade must match or beat
+    #[serde(default = "default_roster_task")]
+    pub task: String,
+    #[serde(default)]
+    pub models: Vec<String>,
+}
+
+impl Default for RosterConfig {
+    fn default() -> Self {
+        Self {
+            task: default_roster_task(),
+            models: Vec::new(),
+        }
+    }
+}
+
+fn default_roster_task() -> String {
+    "coding".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaskConfig {
+    #[serde(default)]
+    pub description: String,
+    /// Sources in priority order; the first is the primary signal
+    pub sources: Vec<TaskSourceConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaskSourceConfig {
+    pub source: String,
+    pub metric: String,
+    /// Column header; defaults to the built-in label for the source
+    pub label: Option<String>,
+    #[serde(default)]
+    pub sort: SortDirection,
+    #[serde(default = "default_task_weight")]
+    pub weight: f64,
+}
+
+fn default_task_weight() -> f64 {
+    1.0
+}
+
 impl Config {
     pub fn load() -> Result<Self> {
         let path = config_path();
@@ -53,6 +165,7 @@ impl Config {
             let content = std::fs::read_to_string(&path)?;
             let mut config: Config = toml::from_str(&content)?;
             config.aa_api_key = aa_api_key_from_content(&content);
+            config.cost.validate()?;
             config
         } else {
             Config::default()
@@ -129,3 +242,18 @@ fn sources_path() -> PathBuf {
         .join("pondus")
         .join("sources.toml")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_ratio_outside_unit_range_is_rejected() {
+        let config: Config = toml::from_str("[cost]\ncached_ratio = 2").unwrap();
+        let err = config.cost.validate().unwrap_err();
+        assert!(err.to_string().contains("cached_ratio"), "{err}");
+
+        let config: Config = toml::from_str("[cost]\ncached_ratio = 0.5").unwrap();
+        assert!(config.cost.validate().is_ok());
+    }
+}
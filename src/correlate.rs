@@ -0,0 +1,365 @@
+use crate::aggregate::{ResolvedSource, resolve_sources};
+use crate::alias::AliasMap;
+use crate::models::SourceResult;
+use crate::output::{OutputFormat, padded_row};
+use crate::variant::VariantPolicy;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Fewer shared models than this and a correlation is reported as missing.
+const MIN_OVERLAP: usize = 3;
+
+/// Pairwise agreement between sources. Every matrix is indexed like `sources`.
+#[derive(Debug, Serialize)]
+pub struct CorrelationMatrix {
+    pub timestamp: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    pub sources: Vec<String>,
+    /// Spearman's ρ over the models both sources list
+    pub spearman: Vec<Vec<Option<f64>>>,
+    /// Kendall's τ-b over the models both sources list
+    pub kendall: Vec<Vec<Option<f64>>>,
+    /// Number of models both sources list (the diagonal is the source's own count)
+    pub overlap: Vec<Vec<usize>>,
+}
+
+/// Join sources on canonical model name and correlate their primary metrics.
+/// Each source contributes its best-ranked row per model.
+pub fn correlate(
+    results: &[SourceResult],
+    aliases: &AliasMap,
+    tag: Option<&str>,
+) -> CorrelationMatrix {
+    let mut sources = resolve_sources(results, aliases, VariantPolicy::Best);
+    sources.sort_by(|a, b| a.source.cmp(&b.source));
+
+    let values: Vec<HashMap<&str, f64>> = sources.iter().map(values_by_model).collect();
+    let n = sources.len();
+    let mut spearman = vec![vec![None; n]; n];
+    let mut kendall = vec![vec![None; n]; n];
+    let mut overlap = vec![vec![0; n]; n];
+
+    for i in 0..n {
+        for j in i..n {
+            let pairs: Vec<(f64, f64)> = values[i]
+                .iter()
+                .filter_map(|(model, x)| values[j].get(model).map(|y| (*x, *y)))
+                .collect();
+            overlap[i][j] = pairs.len();
+            overlap[j][i] = pairs.len();
+            if pairs.len() < MIN_OVERLAP {
+                continue;
+            }
+            let rho = spearman_rho(&pairs);
+            let tau = kendall_tau_b(&pairs);
+            spearman[i][j] = rho;
+            spearman[j][i] = rho;
+            kendall[i][j] = tau;
+            kendall[j][i] = tau;
+        }
+    }
+
+    CorrelationMatrix {
+        timestamp: Utc::now(),
+        tag: tag.map(str::to_string),
+        sources: sources.into_iter().map(|s| s.source).collect(),
+        spearman,
+        kendall,
+        overlap,
+    }
+}
+
+pub fn render(matrix: &CorrelationMatrix, format: OutputFormat) -> Result<String> {
+    Ok(match format {
+        OutputFormat::Json => serde_json::to_string_pretty(matrix)?,
+        OutputFormat::Table => render_table(matrix),
+        OutputFormat::Markdown => render_markdown(matrix),
+    })
+}
+
+fn values_by_model(source: &ResolvedSource) -> HashMap<&str, f64> {
+    source
+        .rows
+        .iter()
+        .map(|row| (row.model.as_str(), row.value))
+        .collect()
+}
+
+/// Pearson correlation of the mid-ranks of `x` and `y`.
+fn spearman_rho(pairs: &[(f64, f64)]) -> Option<f64> {
+    let xs: Vec<f64> = pairs.iter().map(|p| p.0).collect();
+    let ys: Vec<f64> = pairs.iter().map(|p| p.1).collect();
+    pearson(&mid_ranks(&xs), &mid_ranks(&ys))
+}
+
+/// Kendall's τ-b, which corrects for ties in either source.
+fn kendall_tau_b(pairs: &[(f64, f64)]) -> Option<f64> {
+    let (mut concordant, mut discordant) = (0.0, 0.0);
+    let (mut tied_x, mut tied_y) = (0.0, 0.0);
+
+    for (i, a) in pairs.iter().enumerate() {
+        for b in &pairs[i + 1..] {
+            let dx = a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal);
+            let dy = a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal);
+            match (dx, dy) {
+                (Ordering::Equal, Ordering::Equal) => {
+                    tied_x += 1.0;
+                    tied_y += 1.0;
+                }
+                (Ordering::Equal, _) => tied_x += 1.0,
+                (_, Ordering::Equal) => tied_y += 1.0,
+                _ if dx == dy => concordant += 1.0,
+                _ => discordant += 1.0,
+            }
+        }
+    }
+
+    let n = pairs.len() as f64;
+    let total = n * (n - 1.0) / 2.0;
+    let denom = ((total - tied_x) * (total - tied_y)).sqrt();
+    (denom > 0.0).then(|| (concordant - discordant) / denom)
+}
+
+/// 1-based ranks (ascending), ties sharing the mean of the positions they span.
+fn mid_ranks(values: &[f64]) -> Vec<f64> {
+    values
+        .iter()
+        .map(|v| {
+            let below = values.iter().filter(|other| *other < v).count() as f64;
+            let tied = values.iter().filter(|other| *other == v).count() as f64;
+            below + (tied + 1.0) / 2.0
+        })
+        .collect()
+}
+
+fn pearson(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+    let cov: f64 = xs
+        .iter()
+        .zip(ys)
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let var_x: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+    let var_y: f64 = ys.iter().map(|y| (y - mean_y).powi(2)).sum();
+    let denom = (var_x * var_y).sqrt();
+    (denom > 0.0).then(|| cov / denom)
+}
+
+/// Mean ρ between a source and every other source it can be compared with.
+/// High values mean the source mostly repeats what the others already say.
+fn mean_agreement(matrix: &CorrelationMatrix, i: usize) -> Option<f64> {
+    let others: Vec<f64> = (0..matrix.sources.len())
+        .filter(|j| *j != i)
+        .filter_map(|j| matrix.spearman[i][j])
+        .collect();
+    (!others.is_empty()).then(|| others.iter().sum::<f64>() / others.len() as f64)
+}
+
+fn blocks(matrix: &CorrelationMatrix) -> Vec<(&'static str, Vec<Vec<String>>)> {
+    let format_corr = |value: &Option<f64>| {
+        value
+            .map(|v| format!("{v:.2}"))
+            .unwrap_or_else(|| "—".to_string())
+    };
+    let n = matrix.sources.len();
+
+    let spearman = (0..n)
+        .map(|i| {
+            let mut row: Vec<String> = matrix.spearman[i].iter().map(format_corr).collect();
+            row.push(format_corr(&mean_agreement(matrix, i)));
+            row
+        })
+        .collect();
+    let kendall = (0..n)
+        .map(|i| matrix.kendall[i].iter().map(format_corr).collect())
+        .collect();
+    let overlap = (0..n)
+        .map(|i| matrix.overlap[i].iter().map(usize::to_string).collect())
+        .collect();
+
+    vec![
+        ("Spearman ρ", spearman),
+        ("Kendall τ-b", kendall),
+        ("Overlap (models in common)", overlap),
+    ]
+}
+
+fn headers(matrix: &CorrelationMatrix, title: &str) -> Vec<String> {
+    let mut headers = vec!["Source".to_string()];
+    headers.extend(matrix.sources.iter().cloned());
+    if title.starts_with("Spearman") {
+        headers.push("mean ρ".to_string());
+    }
+    headers
+}
+
+fn render_table(matrix: &CorrelationMatrix) -> String {
+    let mut lines = Vec::new();
+    if let Some(tag) = &matrix.tag {
+        lines.push(format!("Tag: {tag}"));
+        lines.push(String::new());
+    }
+
+    for (title, cells) in blocks(matrix) {
+        let headers = headers(matrix, title);
+        let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+        let rows: Vec<Vec<String>> = matrix
+            .sources
+            .iter()
+            .zip(cells)
+            .map(|(source, cells)| {
+                let mut row = vec![source.clone()];
+                row.extend(cells);
+                row
+            })
+            .collect();
+        for row in &rows {
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = widths[i].max(cell.chars().count());
+            }
+        }
+
+        lines.push(title.to_string());
+        lines.push(padded_row(&headers, &widths));
+        lines.push(
+            widths
+                .iter()
+                .map(|w| "-".repeat(*w))
+                .collect::<Vec<_>>()
+                .join("  "),
+        );
+        for row in rows {
+            lines.push(padded_row(&row, &widths));
+        }
+        lines.push(String::new());
+    }
+
+    lines.push(format!(
+        "Correlations need at least {MIN_OVERLAP} shared models; — marks pairs with fewer."
+    ));
+    lines.join("\n")
+}
+
+fn render_markdown(matrix: &CorrelationMatrix) -> String {
+    let mut lines = Vec::new();
+    if let Some(tag) = &matrix.tag {
+        lines.push(format!("**Tag:** `{tag}`"));
+        lines.push(String::new());
+    }
+
+    for (title, cells) in blocks(matrix) {
+        let headers = headers(matrix, title);
+        lines.push(format!("### {title}"));
+        lines.push(String::new());
+        lines.push(format!("| {} |", headers.join(" | ")));
+        lines.push(format!(
+            "| {} |",
+            headers
+                .iter()
+                .map(|_| "---")
+                .collect::<Vec<_>>()
+                .join(" | ")
+        ));
+        for (source, cells) in matrix.sources.iter().zip(cells) {
+            lines.push(format!("| {} | {} |", source, cells.join(" | ")));
+        }
+        lines.push(String::new());
+    }
+
+    lines.push(format!(
+        "Correlations need at least {MIN_OVERLAP} shared models; — marks pairs with fewer."
+    ));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MetricValue, ModelScore, SourceStatus};
+    use crate::variant::parse_variant;
+
+    fn source(name: &str, metric: &str, rows: &[(&str, f64)]) -> SourceResult {
+        SourceResult {
+            source: name.to_string(),
+            fetched_at: None,
+            status: SourceStatus::Ok,
+            scores: rows
+                .iter()
+                .enumerate()
+                .map(|(i, (model, value))| ModelScore {
+                    model: model.to_string(),
+                    source_model_name: model.to_string(),
+                    variant: parse_variant(model),
+                    agent: None,
+                    metrics: HashMap::from([(metric.to_string(), MetricValue::Float(*value))]),
+                    rank: Some((i + 1) as u32),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn spearman_perfect_and_reversed() {
+        let agree = [(1.0, 10.0), (2.0, 20.0), (3.0, 35.0)];
+        let reverse = [(1.0, 3.0), (2.0, 2.0), (3.0, 1.0)];
+        assert!((spearman_rho(&agree).unwrap() - 1.0).abs() < 1e-9);
+        assert!((spearman_rho(&reverse).unwrap() + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn kendall_tau_b_corrects_for_ties() {
+        let pairs = [(1.0, 1.0), (2.0, 2.0), (3.0, 2.0), (4.0, 3.0)];
+        // 5 concordant, 0 discordant, 1 pair tied in y: 5 / sqrt(6 * 5)
+        let expected = 5.0 / 30.0_f64.sqrt();
+        assert!((kendall_tau_b(&pairs).unwrap() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn correlate_joins_on_canonical_names() {
+        let aliases = AliasMap::bundled();
+        let arena = source(
+            "arena",
+            "elo_score",
+            &[
+                ("claude-opus-4-6", 1500.0),
+                ("gpt-5.2", 1480.0),
+                ("gemini-3-pro", 1460.0),
+                ("grok-4", 1400.0),
+            ],
+        );
+        let seal = source(
+            "seal",
+            "overall_score",
+            &[
+                ("Claude Opus 4.6", 80.0),
+                ("GPT-5.2", 70.0),
+                ("Gemini 3 Pro", 60.0),
+                ("only-in-seal", 50.0),
+            ],
+        );
+
+        let matrix = correlate(&[seal, arena], &aliases, None);
+        assert_eq!(matrix.sources, vec!["arena", "seal"]);
+        assert_eq!(matrix.overlap[0][1], 3);
+        assert_eq!(matrix.overlap[1][1], 4);
+        assert!((matrix.spearman[0][1].unwrap() - 1.0).abs() < 1e-9);
+        assert!((matrix.kendall[1][0].unwrap() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn small_overlap_has_no_correlation() {
+        let aliases = AliasMap::bundled();
+        let a = source("arena", "elo_score", &[("x", 2.0), ("y", 1.0)]);
+        let b = source("seal", "overall_score", &[("x", 2.0), ("y", 1.0)]);
+
+        let matrix = correlate(&[a, b], &aliases, None);
+        assert_eq!(matrix.overlap[0][1], 2);
+        assert!(matrix.spearman[0][1].is_none());
+    }
+}
@@ -0,0 +1,437 @@
+use crate::alias::AliasMap;
+use crate::config::CostConfig;
+use crate::models::{ModelScore, SourceResult};
+use crate::output::{OutputFormat, padded_row};
+use crate::pareto::{MetricRef, metric_by_model, metric_f64};
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+const PRICE_SOURCE: &str = "openrouter";
+
+/// How many tokens a workload sends and receives.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TokenMix {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Share of input tokens served from the prompt cache (0–1)
+    pub cached_ratio: f64,
+}
+
+impl TokenMix {
+    pub fn from_config(config: &CostConfig) -> Self {
+        Self {
+            input_tokens: config.input_tokens,
+            output_tokens: config.output_tokens,
+            cached_ratio: config.cached_ratio,
+        }
+    }
+
+    /// What this mix costs at the OpenRouter prices on `score`. Cached input is
+    /// billed at the cache-read price, or the input price if none is listed.
+    pub fn spend(&self, score: &ModelScore) -> Option<Spend> {
+        let prompt = metric_f64(score, "prompt_per_1m")?;
+        let completion = metric_f64(score, "completion_per_1m")?;
+        let cache_read = metric_f64(score, "cache_read_per_1m");
+
+        let cached_tokens = self.input_tokens as f64 * self.cached_ratio;
+        let uncached_tokens = self.input_tokens as f64 - cached_tokens;
+        let input = uncached_tokens * prompt / 1_000_000.0;
+        let cached_input = cached_tokens * cache_read.unwrap_or(prompt) / 1_000_000.0;
+        let output = self.output_tokens as f64 * completion / 1_000_000.0;
+
+        Some(Spend {
+            input,
+            cached_input,
+            output,
+            total: input + cached_input + output,
+            cache_priced: cache_read.is_some(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Spend {
+    pub input: f64,
+    pub cached_input: f64,
+    pub output: f64,
+    pub total: f64,
+    /// Whether the model lists a cache-read price (otherwise cached input is billed as input)
+    pub cache_priced: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CostRow {
+    pub rank: usize,
+    pub model: String,
+    #[serde(flatten)]
+    pub spend: Spend,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CostOutput {
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub mix: TokenMix,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<MetricRef>,
+    pub rows: Vec<CostRow>,
+}
+
+/// Parse a token count such as `300k`, `2M`, `1.5m` or `2_000_000`.
+pub fn parse_token_count(value: &str) -> Result<u64, String> {
+    let cleaned = value.trim().replace(['_', ','], "");
+    let (number, multiplier) = match cleaned.char_indices().last() {
+        Some((i, 'k' | 'K')) => (&cleaned[..i], 1e3),
+        Some((i, 'm' | 'M')) => (&cleaned[..i], 1e6),
+        Some((i, 'b' | 'B')) => (&cleaned[..i], 1e9),
+        _ => (cleaned.as_str(), 1.0),
+    };
+    match number.parse::<f64>() {
+        Ok(n) if n.is_finite() && n >= 0.0 => Ok((n * multiplier).round() as u64),
+        _ => Err(format!(
+            "invalid token count '{value}' (expected e.g. 300k, 2M or 1500000)"
+        )),
+    }
+}
+
+/// Price `mix` for every model OpenRouter lists, cheapest first. With `quality`,
+/// only models that also have that metric are kept.
+pub fn estimate(
+    results: &[SourceResult],
+    aliases: &AliasMap,
+    mix: TokenMix,
+    quality: Option<&MetricRef>,
+) -> Result<CostOutput> {
+    if !(0.0..=1.0).contains(&mix.cached_ratio) {
+        bail!(
+            "--cached-ratio must be between 0 and 1, got {}",
+            mix.cached_ratio
+        );
+    }
+
+    let Some(prices) = results.iter().find(|r| r.source == PRICE_SOURCE) else {
+        bail!("No {PRICE_SOURCE} pricing data available");
+    };
+    let qualities = quality
+        .map(|metric| metric_by_model(results, aliases, metric, Ordering::Greater))
+        .transpose()?;
+
+    let mut cheapest: HashMap<String, Spend> = HashMap::new();
+    for score in &prices.scores {
+        let Some(spend) = mix.spend(score) else {
+            continue;
+        };
+        let model = aliases.canonical_for(score);
+        match cheapest.get(&model) {
+            Some(existing) if existing.total <= spend.total => {}
+            _ => {
+                cheapest.insert(model, spend);
+            }
+        }
+    }
+
+    let mut rows: Vec<(String, Spend, Option<f64>)> = cheapest
+        .into_iter()
+        .filter_map(|(model, spend)| match &qualities {
+            Some(qualities) => {
+                let quality = *qualities.get(&model)?;
+                Some((model, spend, Some(quality)))
+            }
+            None => Some((model, spend, None)),
+        })
+        .collect();
+    if let (Some(metric), true) = (quality, rows.is_empty()) {
+        bail!("No model has both {PRICE_SOURCE} pricing and {metric}");
+    }
+    rows.sort_by(|a, b| a.1.total.total_cmp(&b.1.total).then_with(|| a.0.cmp(&b.0)));
+
+    Ok(CostOutput {
+        timestamp: Utc::now(),
+        mix,
+        quality: quality.cloned(),
+        rows: rows
+            .into_iter()
+            .enumerate()
+            .map(|(i, (model, spend, quality))| CostRow {
+                rank: i + 1,
+                model,
+                spend,
+                quality,
+            })
+            .collect(),
+    })
+}
+
+pub fn render(output: &CostOutput, format: OutputFormat) -> Result<String> {
+    Ok(match format {
+        OutputFormat::Json => serde_json::to_string_pretty(output)?,
+        OutputFormat::Table => render_table(output),
+        OutputFormat::Markdown => render_markdown(output),
+    })
+}
+
+pub fn format_tokens(tokens: u64) -> String {
+    let tokens = tokens as f64;
+    if tokens >= 1e9 {
+        format!("{}B", trim_decimal(tokens / 1e9))
+    } else if tokens >= 1e6 {
+        format!("{}M", trim_decimal(tokens / 1e6))
+    } else if tokens >= 1e3 {
+        format!("{}k", trim_decimal(tokens / 1e3))
+    } else {
+        format!("{tokens}")
+    }
+}
+
+fn trim_decimal(value: f64) -> String {
+    let formatted = format!("{value:.1}");
+    formatted
+        .strip_suffix(".0")
+        .map(str::to_string)
+        .unwrap_or(formatted)
+}
+
+fn format_dollars(value: f64) -> String {
+    format!("${value:.2}")
+}
+
+fn mix_summary(mix: &TokenMix) -> String {
+    let cached = if mix.cached_ratio > 0.0 {
+        format!(" ({:.0}% cached)", mix.cached_ratio * 100.0)
+    } else {
+        String::new()
+    };
+    format!(
+        "{} input{cached} · {} output",
+        format_tokens(mix.input_tokens),
+        format_tokens(mix.output_tokens)
+    )
+}
+
+fn headers(output: &CostOutput) -> Vec<String> {
+    let mut headers = vec!["Rank".to_string(), "Model".to_string(), "Input".to_string()];
+    if output.mix.cached_ratio > 0.0 {
+        headers.push("Cached".to_string());
+    }
+    headers.extend(["Output".to_string(), "Total".to_string()]);
+    if let Some(metric) = &output.quality {
+        headers.push(metric.to_string());
+    }
+    headers
+}
+
+fn cells(output: &CostOutput, row: &CostRow) -> Vec<String> {
+    let mut cells = vec![
+        row.rank.to_string(),
+        row.model.clone(),
+        format_dollars(row.spend.input),
+    ];
+    if output.mix.cached_ratio > 0.0 {
+        let marker = if row.spend.cache_priced { "" } else { "*" };
+        cells.push(format!(
+            "{}{marker}",
+            format_dollars(row.spend.cached_input)
+        ));
+    }
+    cells.extend([
+        format_dollars(row.spend.output),
+        format_dollars(row.spend.total),
+    ]);
+    if output.quality.is_some() {
+        cells.push(
+            row.quality
+                .map(|q| format!("{q:.2}"))
+                .unwrap_or_else(|| "—".to_string()),
+        );
+    }
+    cells
+}
+
+fn footnote(output: &CostOutput) -> Option<&'static str> {
+    let uncached = output.rows.iter().any(|row| !row.spend.cache_priced);
+    (output.mix.cached_ratio > 0.0 && uncached)
+        .then_some("* no cache-read price listed; cached input billed at the input rate")
+}
+
+fn render_table(output: &CostOutput) -> String {
+    let mut lines = vec![
+        format!("Token mix: {}", mix_summary(&output.mix)),
+        String::new(),
+    ];
+
+    let headers = headers(output);
+    let rows: Vec<Vec<String>> = output.rows.iter().map(|row| cells(output, row)).collect();
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in &rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    lines.push(padded_row(&headers, &widths));
+    lines.push(
+        widths
+            .iter()
+            .map(|w| "-".repeat(*w))
+            .collect::<Vec<_>>()
+            .join("  "),
+    );
+    for row in rows {
+        lines.push(padded_row(&row, &widths));
+    }
+    if let Some(note) = footnote(output) {
+        lines.push(String::new());
+        lines.push(note.to_string());
+    }
+    lines.join("\n")
+}
+
+fn render_markdown(output: &CostOutput) -> String {
+    let headers = headers(output);
+    let mut lines = vec![
+        format!("**Token mix:** {}", mix_summary(&output.mix)),
+        String::new(),
+        format!("| {} |", headers.join(" | ")),
+        format!(
+            "| {} |",
+            headers
+                .iter()
+                .map(|_| "---")
+                .collect::<Vec<_>>()
+                .join(" | ")
+        ),
+    ];
+    for row in &output.rows {
+        lines.push(format!("| {} |", cells(output, row).join(" | ")));
+    }
+    if let Some(note) = footnote(output) {
+        lines.push(String::new());
+        lines.push(note.replacen('*', "\\*", 1));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MetricValue, SourceStatus};
+    use crate::variant::parse_variant;
+
+    fn price(model: &str, prompt: f64, completion: f64, cache_read: Option<f64>) -> ModelScore {
+        let mut metrics = HashMap::from([
+            ("prompt_per_1m".to_string(), MetricValue::Float(prompt)),
+            (
+                "completion_per_1m".to_string(),
+                MetricValue::Float(completion),
+            ),
+        ]);
+        if let Some(cache_read) = cache_read {
+            metrics.insert(
+                "cache_read_per_1m".to_string(),
+                MetricValue::Float(cache_read),
+            );
+        }
+        ModelScore {
+            model: model.to_string(),
+            source_model_name: model.to_string(),
+            variant: parse_variant(model),
+            agent: None,
+            metrics,
+            rank: None,
+        }
+    }
+
+    fn source(name: &str, scores: Vec<ModelScore>) -> SourceResult {
+        SourceResult {
+            source: name.to_string(),
+            fetched_at: None,
+            status: SourceStatus::Ok,
+            scores,
+        }
+    }
+
+    fn mix(input: u64, output: u64, cached_ratio: f64) -> TokenMix {
+        TokenMix {
+            input_tokens: input,
+            output_tokens: output,
+            cached_ratio,
+        }
+    }
+
+    #[test]
+    fn parses_token_counts_with_suffixes() {
+        assert_eq!(parse_token_count("2M"), Ok(2_000_000));
+        assert_eq!(parse_token_count("300k"), Ok(300_000));
+        assert_eq!(parse_token_count("1.5m"), Ok(1_500_000));
+        assert_eq!(parse_token_count("2_000_000"), Ok(2_000_000));
+        assert!(parse_token_count("lots").is_err());
+        assert!(parse_token_count("-1k").is_err());
+    }
+
+    #[test]
+    fn cached_input_uses_cache_read_price() {
+        let score = price("x", 5.0, 25.0, Some(0.5));
+        let spend = mix(2_000_000, 300_000, 0.5).spend(&score).unwrap();
+        assert!((spend.input - 5.0).abs() < 1e-9);
+        assert!((spend.cached_input - 0.5).abs() < 1e-9);
+        assert!((spend.output - 7.5).abs() < 1e-9);
+        assert!((spend.total - 13.0).abs() < 1e-9);
+        assert!(spend.cache_priced);
+    }
+
+    #[test]
+    fn cached_input_falls_back_to_input_price() {
+        let score = price("x", 2.0, 10.0, None);
+        let spend = mix(1_000_000, 0, 0.5).spend(&score).unwrap();
+        assert!((spend.total - 2.0).abs() < 1e-9);
+        assert!(!spend.cache_priced);
+    }
+
+    #[test]
+    fn estimate_sorts_cheapest_first_and_joins_quality() {
+        let aliases = AliasMap::bundled();
+        let prices = source(
+            "openrouter",
+            vec![
+                price("pricey", 10.0, 30.0, None),
+                price("cheap", 1.0, 2.0, None),
+                price("unscored", 0.1, 0.2, None),
+            ],
+        );
+        let mut quality = source("swebench", vec![]);
+        for (model, rate) in [("pricey", 80.0), ("cheap", 60.0)] {
+            let mut score = price(model, 0.0, 0.0, None);
+            score.metrics = HashMap::from([("resolved_rate".into(), MetricValue::Float(rate))]);
+            quality.scores.push(score);
+        }
+        let results = vec![prices, quality];
+
+        let all = estimate(&results, &aliases, mix(1_000_000, 1_000_000, 0.0), None).unwrap();
+        let models: Vec<&str> = all.rows.iter().map(|r| r.model.as_str()).collect();
+        assert_eq!(models, vec!["unscored", "cheap", "pricey"]);
+
+        let metric: MetricRef = "swebench.resolved_rate".parse().unwrap();
+        let joined = estimate(
+            &results,
+            &aliases,
+            mix(1_000_000, 1_000_000, 0.0),
+            Some(&metric),
+        )
+        .unwrap();
+        let models: Vec<&str> = joined.rows.iter().map(|r| r.model.as_str()).collect();
+        assert_eq!(models, vec!["cheap", "pricey"]);
+        assert_eq!(joined.rows[1].quality, Some(80.0));
+    }
+
+    #[test]
+    fn rejects_out_of_range_cached_ratio() {
+        let aliases = AliasMap::bundled();
+        let results = vec![source("openrouter", vec![])];
+        assert!(estimate(&results, &aliases, mix(1, 1, 1.5), None).is_err());
+    }
+}
@@ -0,0 +1,298 @@
+use crate::aggregate::{
+    AggregateOptions, ModelBreakdown, SourceBreakdown, explain_model, metric_as_f64, primary_metric,
+};
+use crate::alias::AliasMap;
+use crate::models::SourceResult;
+use crate::output::{OutputFormat, padded_row};
+use crate::recommend::{Neighbour, RecommendExplanation};
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// A source row that resolves to the explained model.
+#[derive(Debug, Serialize)]
+pub struct SourceMatch {
+    pub source: String,
+    pub source_model_name: String,
+    /// How the alias map matched the row: exact, alias, prefix or no-match
+    pub match_kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<u32>,
+    /// Whether the aggregate represents the model in this source by this row
+    pub used: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExplainOutput {
+    pub timestamp: DateTime<Utc>,
+    pub query: String,
+    pub model: String,
+    pub method: String,
+    pub coverage: String,
+    pub matches: Vec<SourceMatch>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggregate: Option<ModelBreakdown>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recommend: Option<RecommendExplanation>,
+}
+
+/// Collect every row that resolves to `model` and break down its aggregate
+/// score. `recommend` is attached as is; it needs sources of its own.
+pub fn explain(
+    results: &[SourceResult],
+    aliases: &AliasMap,
+    options: &AggregateOptions,
+    query: &str,
+    model: &str,
+    recommend: Option<RecommendExplanation>,
+) -> Result<ExplainOutput> {
+    let aggregate = explain_model(results, aliases, options, model);
+
+    let mut matches = Vec::new();
+    for result in results {
+        let metric = primary_metric(&result.source);
+        let used_name = aggregate
+            .as_ref()
+            .and_then(|breakdown| {
+                breakdown
+                    .sources
+                    .iter()
+                    .find(|source| source.source == result.source)
+            })
+            .and_then(|source| source.source_model_name.as_deref());
+
+        for score in &result.scores {
+            if aliases.canonical_for(score) != model {
+                continue;
+            }
+            matches.push(SourceMatch {
+                source: result.source.clone(),
+                source_model_name: score.source_model_name.clone(),
+                match_kind: aliases.match_kind_for(score).as_str(),
+                metric,
+                value: metric
+                    .and_then(|metric| score.metrics.get(metric))
+                    .and_then(metric_as_f64),
+                rank: score.rank,
+                used: used_name == Some(score.source_model_name.as_str()),
+            });
+        }
+    }
+
+    if matches.is_empty() {
+        bail!("'{query}' not found in any source. Try: pondus check {query} --show-matches");
+    }
+
+    Ok(ExplainOutput {
+        timestamp: Utc::now(),
+        query: query.to_string(),
+        model: model.to_string(),
+        method: value_name(options.method),
+        coverage: value_name(options.coverage),
+        matches,
+        aggregate,
+        recommend,
+    })
+}
+
+fn value_name(value: impl ValueEnum) -> String {
+    value
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+pub fn render(output: &ExplainOutput, format: OutputFormat) -> Result<String> {
+    Ok(match format {
+        OutputFormat::Json => serde_json::to_string_pretty(output)?,
+        OutputFormat::Table => render_text(output, false),
+        OutputFormat::Markdown => render_text(output, true),
+    })
+}
+
+fn render_text(output: &ExplainOutput, markdown: bool) -> String {
+    let heading = |text: String| {
+        if markdown {
+            format!("### {text}")
+        } else {
+            text
+        }
+    };
+
+    let mut lines = vec![if output.query.to_lowercase() == output.model {
+        heading(output.model.clone())
+    } else {
+        heading(format!("{} (from \"{}\")", output.model, output.query))
+    }];
+
+    lines.push(String::new());
+    lines.push(format!(
+        "{} matching row(s); * marks the row each source is aggregated by",
+        output.matches.len()
+    ));
+    let rows: Vec<Vec<String>> = output.matches.iter().map(match_cells).collect();
+    lines.extend(table(
+        &["Source", "Listed as", "Match", "Rank", "Value"],
+        &rows,
+        markdown,
+    ));
+
+    lines.push(String::new());
+    match &output.aggregate {
+        Some(breakdown) => {
+            lines.push(heading(aggregate_summary(output, breakdown)));
+            lines.push(String::new());
+            let rows: Vec<Vec<String>> = breakdown.sources.iter().map(breakdown_cells).collect();
+            lines.extend(table(
+                &["Source", "Rank", "Score", "Weight", "Contribution"],
+                &rows,
+                markdown,
+            ));
+            if let Some(shrinkage) = breakdown.shrinkage {
+                lines.push(format!("Coverage shrinkage: {shrinkage:+.3}"));
+            }
+        }
+        None => lines.push("Not part of the aggregate.".to_string()),
+    }
+
+    if let Some(recommend) = &output.recommend {
+        lines.push(String::new());
+        lines.extend(recommend_lines(recommend, markdown, &heading));
+    }
+
+    lines.join("\n")
+}
+
+fn match_cells(row: &SourceMatch) -> Vec<String> {
+    let value = match (row.metric, row.value) {
+        (Some(metric), Some(value)) => format!("{value:.2} {metric}"),
+        _ => "—".to_string(),
+    };
+    vec![
+        format!("{}{}", row.source, if row.used { " *" } else { "" }),
+        row.source_model_name.clone(),
+        row.match_kind.to_string(),
+        row.rank
+            .map_or_else(|| "—".to_string(), |rank| rank.to_string()),
+        value,
+    ]
+}
+
+fn aggregate_summary(output: &ExplainOutput, breakdown: &ModelBreakdown) -> String {
+    let position = match breakdown.rank {
+        Some(rank) => format!("rank {rank} of {}", breakdown.ranked),
+        None => format!(
+            "unranked ({} source(s), below --min-sources)",
+            breakdown.sources_count
+        ),
+    };
+    format!(
+        "Aggregate ({}, coverage {}): {position}, score {:.3}, spread {:.3}, coverage {:.0}%",
+        output.method,
+        output.coverage,
+        breakdown.avg_percentile,
+        breakdown.spread,
+        breakdown.coverage * 100.0
+    )
+}
+
+fn breakdown_cells(source: &SourceBreakdown) -> Vec<String> {
+    let rank = match source.rank {
+        Some(rank) => format!("{rank}/{}", source.total),
+        None if source.imputed => "imputed".to_string(),
+        None => "—".to_string(),
+    };
+    vec![
+        source.source.clone(),
+        rank,
+        source
+            .score
+            .map_or_else(|| "—".to_string(), |score| format!("{score:.3}")),
+        format!("{:.2}", source.weight),
+        source
+            .contribution
+            .map_or_else(|| "—".to_string(), |c| format!("{c:.3}")),
+    ]
+}
+
+fn recommend_lines(
+    recommend: &RecommendExplanation,
+    markdown: bool,
+    heading: &dyn Fn(String) -> String,
+) -> Vec<String> {
+    let task = &recommend.task;
+    let scoring = value_name(recommend.scoring);
+    let position = match (recommend.rank, recommend.composite) {
+        (Some(rank), Some(composite)) => format!(
+            "rank {rank} of {}, composite {composite:.3}",
+            recommend.total
+        ),
+        (Some(rank), None) => format!("rank {rank} of {}", recommend.total),
+        (None, _) => "not ranked".to_string(),
+    };
+    let mut lines = vec![
+        heading(format!("Recommend {task} ({scoring}): {position}")),
+        String::new(),
+    ];
+
+    let mut headers = vec![""];
+    headers.extend(recommend.metrics.iter().map(|metric| metric.label.as_str()));
+    let mut values = vec!["Value".to_string()];
+    values.extend(recommend.metrics.iter().map(|metric| metric.display()));
+    let mut components = vec!["Percentile".to_string()];
+    components.extend(recommend.metrics.iter().map(|metric| {
+        metric
+            .component
+            .map_or_else(|| "—".to_string(), |c| format!("{c:.3}"))
+    }));
+    lines.extend(table(&headers, &[values, components], markdown));
+
+    let neighbour = |label: &str, neighbour: &Neighbour| {
+        format!(
+            "{label} #{} {}: decided by {}, {}",
+            neighbour.rank, neighbour.model, neighbour.decided_by, neighbour.detail
+        )
+    };
+    let bullet = if markdown { "- " } else { "" };
+    if recommend.above.is_some() || recommend.below.is_some() {
+        lines.push(String::new());
+    }
+    if let Some(above) = &recommend.above {
+        lines.push(format!("{bullet}{}", neighbour("Behind", above)));
+    }
+    if let Some(below) = &recommend.below {
+        lines.push(format!("{bullet}{}", neighbour("Ahead of", below)));
+    }
+    lines
+}
+
+fn table(headers: &[&str], rows: &[Vec<String>], markdown: bool) -> Vec<String> {
+    let headers: Vec<String> = headers.iter().map(|header| header.to_string()).collect();
+    if markdown {
+        let mut lines = vec![
+            format!("| {} |", headers.join(" | ")),
+            format!("| {} |", vec!["---"; headers.len()].join(" | ")),
+        ];
+        for row in rows {
+            lines.push(format!("| {} |", row.join(" | ").replace(" *", " \\*")));
+        }
+        return lines;
+    }
+
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+    let mut lines = vec![format!("  {}", padded_row(&headers, &widths))];
+    for row in rows {
+        lines.push(format!("  {}", padded_row(row, &widths)));
+    }
+    lines
+}
@@ -1,29 +1,48 @@
+mod advise;
+mod aggregate;
 mod alias;
+mod bootstrap;
 mod cache;
+mod compare;
 mod config;
+mod correlate;
+mod cost;
+mod explain;
 mod models;
 mod monitor;
+mod outliers;
 mod output;
+mod pareto;
+mod ranking;
 mod recommend;
+mod routing;
 mod sources;
+mod variant;
 
-use alias::{AliasMap, MatchKind};
+use aggregate::{
+    AggregateMethod, AggregateOptions, Coverage, aggregate_results, aggregate_sources,
+    excluded_models,
+};
+use alias::AliasMap;
 use anyhow::Result;
+use bootstrap::{BootstrapOptions, bootstrap_ranks};
 use cache::Cache;
 use chrono::{Duration, Local, Utc};
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use config::Config;
-use models::{
-    MetricValue, ModelScore, PondusOutput, QueryInfo, SourceResult, SourceStatus, SourceTag,
-};
+use cost::TokenMix;
+use models::{MetricValue, PondusOutput, QueryInfo, SourceResult, SourceTag};
 use monitor::MonitorCommand;
 use output::OutputFormat;
-use recommend::RecommendTask;
+use pareto::MetricRef;
+use ranking::TieMode;
+use recommend::{Constraints, Scoring};
 use sources::Source;
-use sources::aa::{AaEffortFilter, classify_effort_level};
-use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::thread;
+use variant::{
+    EffortFilter, VariantPolicy, apply_effort_filter, apply_scaffold_filter, keep_best_harness,
+};
 
 /// The month LiveBench last published new data. Update this const whenever the
 /// dataset is refreshed — the staleness warning below is derived from it so
@@ -41,7 +60,7 @@ struct Cli {
     #[command(subcommand)]
     command: Option<Command>,
 
-    /// Output format: json (default), table, markdown
+    /// Output format: json (default), table, markdown; export-routing takes litellm, openrouter-fallbacks, json
     #[arg(long, default_value = "json", global = true)]
     format: String,
 
@@ -50,57 +69,95 @@ struct Cli {
     refresh: bool,
 }
 
+#[derive(Args, Default)]
+struct RankArgs {
+    /// Show top N models
+    #[arg(long)]
+    top: Option<usize>,
+    /// Filter to a single source name (case-insensitive)
+    #[arg(long)]
+    source: Option<String>,
+    /// Filter to source tags: reasoning, coding, agentic, general
+    #[arg(long)]
+    tag: Option<String>,
+    /// Comma-separated source names (case-insensitive)
+    #[arg(long)]
+    sources: Option<String>,
+    /// Produce a combined leaderboard across sources
+    #[arg(long)]
+    aggregate: bool,
+    /// Minimum number of sources a model must appear in (default: 2 when --aggregate is set)
+    #[arg(long)]
+    min_sources: Option<usize>,
+    /// Show models excluded by --min-sources threshold when aggregating
+    #[arg(long)]
+    show_excluded: bool,
+    /// Exclude sources with data older than N days
+    #[arg(long)]
+    max_age: Option<u64>,
+    /// Show data age for each source in rank output
+    #[arg(long)]
+    show_freshness: bool,
+    /// Filter rows by reasoning effort level (all sources)
+    #[arg(long, value_enum, default_value_t = EffortFilter::All)]
+    effort: EffortFilter,
+    /// Keep only the newest member of each model family (see `family` in models.toml)
+    #[arg(long)]
+    latest_per_family: bool,
+    /// Keep only rows run under this agent scaffold (substring, `none` = no scaffold)
+    #[arg(long)]
+    scaffold: Option<String>,
+    /// Rank each model by its best harness, one row per model per source
+    #[arg(long, conflicts_with = "scaffold")]
+    best_harness: bool,
+    /// How rows with equal scores are numbered within each source
+    #[arg(long, value_enum, default_value_t = TieMode::Competition)]
+    ties: TieMode,
+    /// Which variant represents a model in --aggregate when a source lists several
+    #[arg(long, value_enum, default_value_t = VariantPolicy::Best)]
+    variant: VariantPolicy,
+    /// How --aggregate combines sources
+    #[arg(long, value_enum, default_value_t = AggregateMethod::Percentile)]
+    method: AggregateMethod,
+    /// How --aggregate treats sources that don't list a model
+    #[arg(long, value_enum, default_value_t = Coverage::None)]
+    coverage: Coverage,
+    /// Weight sources by a named `[aggregate.profiles.<name>]` table from config
+    #[arg(long, requires = "aggregate")]
+    profile: Option<String>,
+    /// Resample sources N times to report rank intervals and top-k probabilities
+    #[arg(long, requires = "aggregate")]
+    bootstrap: Option<usize>,
+    /// Seed for --bootstrap resampling
+    #[arg(long, default_value_t = 1, requires = "bootstrap")]
+    seed: u64,
+    /// k for the p_top_<k> probability reported by --bootstrap
+    #[arg(long, default_value_t = 3, requires = "bootstrap")]
+    top_k: usize,
+}
+
 #[derive(Subcommand)]
 enum Command {
     /// Rank all models across sources
-    Rank {
-        /// Show top N models
-        #[arg(long)]
-        top: Option<usize>,
-        /// Filter to a single source name (case-insensitive)
-        #[arg(long)]
-        source: Option<String>,
-        /// Filter to source tags: reasoning, coding, agentic, general
-        #[arg(long)]
-        tag: Option<String>,
-        /// Comma-separated source names (case-insensitive)
-        #[arg(long)]
-        sources: Option<String>,
-        /// Produce a combined leaderboard across sources
-        #[arg(long)]
-        aggregate: bool,
-        /// Minimum number of sources a model must appear in (default: 2 when --aggregate is set)
-        #[arg(long)]
-        min_sources: Option<usize>,
-        /// Show models excluded by --min-sources threshold when aggregating
-        #[arg(long)]
-        show_excluded: bool,
-        /// Exclude sources with data older than N days
-        #[arg(long)]
-        max_age: Option<u64>,
-        /// Show data age for each source in rank output
-        #[arg(long)]
-        show_freshness: bool,
-        /// Filter AA results by reasoning effort level
-        #[arg(long, value_enum, default_value_t = AaEffortFilter::All)]
-        effort: AaEffortFilter,
-    },
+    Rank(RankArgs),
     /// Check a single model across all sources
     Check {
         /// Model name (canonical or alias)
         model: String,
         #[arg(long)]
         show_matches: bool,
+        /// Only show scaffold rows, to compare agent harnesses for this model
+        #[arg(long)]
+        by_scaffold: bool,
     },
-    /// Compare two models head-to-head
+    /// Compare two or more models head-to-head
     Compare {
-        /// First model
-        model1: String,
-        /// Second model
-        model2: String,
-        /// Filter AA results by reasoning effort level
-        #[arg(long, value_enum, default_value_t = AaEffortFilter::All)]
-        effort: AaEffortFilter,
+        /// Models to compare (canonical names or aliases)
+        #[arg(required = true, num_args = 2..)]
+        models: Vec<String>,
+        /// Filter rows by reasoning effort level (all sources)
+        #[arg(long, value_enum, default_value_t = EffortFilter::All)]
+        effort: EffortFilter,
     },
     /// Watch a model across all sources until all have data
     Watch {
@@ -120,21 +177,140 @@ enum Command {
     },
     /// List all sources and their status
     Sources,
+    /// Show how closely sources agree with each other
+    Correlate {
+        /// Only correlate sources with this tag: reasoning, coding, agentic, general
+        #[arg(long)]
+        tag: Option<String>,
+    },
+    /// Cost–quality Pareto frontier across two source metrics
+    Pareto {
+        /// Quality metric as <source>.<metric> (higher is better)
+        #[arg(long, default_value = "swebench.resolved_rate")]
+        quality: MetricRef,
+        /// Cost metric as <source>.<metric> (lower is better); openrouter.blended
+        /// is a 3:1 input:output blend of the per-1M prices
+        #[arg(long, default_value = "openrouter.blended")]
+        cost: MetricRef,
+    },
+    /// Estimate spend per model for a token mix at OpenRouter prices
+    Cost {
+        /// Input tokens, e.g. 2M or 500k (default: [cost] input_tokens)
+        #[arg(long, value_parser = cost::parse_token_count)]
+        input_tokens: Option<u64>,
+        /// Output tokens, e.g. 300k (default: [cost] output_tokens)
+        #[arg(long, value_parser = cost::parse_token_count)]
+        output_tokens: Option<u64>,
+        /// Share of input tokens read from the prompt cache, 0–1 (default: [cost] cached_ratio)
+        #[arg(long)]
+        cached_ratio: Option<f64>,
+        /// Join a quality metric as <source>.<metric>, keeping only models that have it
+        #[arg(long)]
+        quality: Option<MetricRef>,
+        /// Show the N cheapest models only
+        #[arg(long)]
+        top: Option<usize>,
+    },
+    /// Flag sources that place a model far from its other sources
+    Outliers {
+        /// Smallest percentile gap from the other sources' mean that gets flagged (0–1)
+        #[arg(long, default_value_t = 0.4)]
+        threshold: f64,
+        /// Only check models listed by at least this many sources (minimum 3)
+        #[arg(long, default_value_t = 3)]
+        min_sources: usize,
+        /// Only consider sources with this tag: reasoning, coding, agentic, general
+        #[arg(long)]
+        tag: Option<String>,
+    },
+    /// Show how one model's aggregate and recommend positions are computed
+    Explain {
+        /// Model name (canonical or alias)
+        model: String,
+        /// Minimum number of sources a model must appear in to be ranked
+        #[arg(long, default_value_t = 2)]
+        min_sources: usize,
+        /// Which variant represents the model when a source lists several
+        #[arg(long, value_enum, default_value_t = VariantPolicy::Best)]
+        variant: VariantPolicy,
+        /// How sources are combined
+        #[arg(long, value_enum, default_value_t = AggregateMethod::Percentile)]
+        method: AggregateMethod,
+        /// How sources that don't list a model are treated
+        #[arg(long, value_enum, default_value_t = Coverage::None)]
+        coverage: Coverage,
+        /// Weight sources by a named `[aggregate.profiles.<name>]` table from config
+        #[arg(long)]
+        profile: Option<String>,
+        /// Filter rows by reasoning effort level (all sources)
+        #[arg(long, value_enum, default_value_t = EffortFilter::All)]
+        effort: EffortFilter,
+        /// Also explain the model's place in this recommend task
+        #[arg(long)]
+        task: Option<String>,
+        /// How --task orders models
+        #[arg(long, value_enum, default_value_t = Scoring::Composite)]
+        scoring: Scoring,
+    },
     /// Force re-fetch all sources (clears cache)
     Refresh,
     /// Recommend models for a task type
     Recommend {
-        /// Task type to recommend for
-        task: Option<RecommendTask>,
+        /// Task to recommend for: a built-in or a `[recommend.tasks]` entry
+        task: Option<String>,
         /// Print available task types with descriptions
         #[arg(long)]
         list_tasks: bool,
+        /// Rank every task from one fetch, as a rank × task matrix
+        #[arg(long, conflicts_with = "task")]
+        all_tasks: bool,
         /// Show top N models
         #[arg(long, default_value_t = 5)]
         top: usize,
-        /// Filter AA results by reasoning effort level when relevant
-        #[arg(long, value_enum, default_value_t = AaEffortFilter::All)]
-        effort: AaEffortFilter,
+        /// Filter rows by reasoning effort level (all sources)
+        #[arg(long, value_enum, default_value_t = EffortFilter::All)]
+        effort: EffortFilter,
+        /// Keep only the newest member of each model family
+        #[arg(long)]
+        latest_per_family: bool,
+        /// How models are ordered: a weighted composite of every source, or the primary source first
+        #[arg(long, value_enum, default_value_t = Scoring::Composite)]
+        scoring: Scoring,
+        /// Which variant represents a model in every task source: best, standard, max, low or cheapest
+        #[arg(long, value_enum, default_value_t = VariantPolicy::Best)]
+        variant: VariantPolicy,
+        #[command(flatten)]
+        constraints: Constraints,
+    },
+    /// Find cheaper or equal-priced models that beat the [roster] models on a task
+    Advise {
+        /// Models to check instead of the [roster] list
+        models: Vec<String>,
+        /// Recommend task whose metrics decide; defaults to [roster] task
+        #[arg(long)]
+        task: Option<String>,
+        /// Exit with status 2 when any model has an upgrade (errors exit with 1)
+        #[arg(long)]
+        exit_code: bool,
+    },
+    /// Write router config: each task's top model with ordered fallbacks
+    ExportRouting {
+        /// Tasks to route; defaults to every recommend task
+        tasks: Vec<String>,
+        /// Fallbacks to list after each task's primary model
+        #[arg(long, default_value_t = 2)]
+        fallbacks: usize,
+        /// Filter rows by reasoning effort level (all sources)
+        #[arg(long, value_enum, default_value_t = EffortFilter::All)]
+        effort: EffortFilter,
+        /// How models are ordered: a weighted composite of every source, or the primary source first
+        #[arg(long, value_enum, default_value_t = Scoring::Composite)]
+        scoring: Scoring,
+        /// Which variant represents a model in every task source: best, standard, max, low or cheapest
+        #[arg(long, value_enum, default_value_t = VariantPolicy::Best)]
+        variant: VariantPolicy,
+        #[command(flatten)]
+        constraints: Constraints,
     },
 }
 
@@ -143,62 +319,38 @@ fn main() -> Result<()> {
     let config = Config::load()?;
     let cache = Cache::new(config.cache.ttl_hours);
     let aliases = AliasMap::load(config.alias.path.as_deref())?;
-    let format = OutputFormat::from_str(&cli.format)?;
 
     if cli.refresh {
         cache.clear()?;
     }
 
-    let command = cli.command.unwrap_or(Command::Rank {
-        top: None,
-        source: None,
-        tag: None,
-        sources: None,
-        aggregate: false,
-        min_sources: None,
-        show_excluded: false,
-        max_age: None,
-        show_freshness: false,
-        effort: AaEffortFilter::All,
-    });
+    let command = cli
+        .command
+        .unwrap_or_else(|| Command::Rank(RankArgs::default()));
+    // export-routing reads --format as a router config flavour instead
+    let format = match command {
+        Command::ExportRouting { .. } => OutputFormat::Json,
+        _ => OutputFormat::from_str(&cli.format)?,
+    };
 
     match command {
-        Command::Rank {
-            top,
-            source,
-            tag,
-            sources,
-            aggregate,
-            min_sources,
-            show_excluded,
-            max_age,
-            show_freshness,
-            effort,
-        } => cmd_rank(
+        Command::Rank(args) => cmd_rank(&config, &cache, &aliases, format, &args),
+        Command::Check {
+            model,
+            show_matches,
+            by_scaffold,
+        } => cmd_check(
             &config,
             &cache,
             &aliases,
             format,
-            top,
-            source.as_deref(),
-            tag.as_deref(),
-            sources.as_deref(),
-            aggregate,
-            min_sources,
-            show_excluded,
-            max_age,
-            show_freshness,
-            effort,
-        ),
-        Command::Check {
-            model,
+            &model,
             show_matches,
-        } => cmd_check(&config, &cache, &aliases, format, &model, show_matches),
-        Command::Compare {
-            model1,
-            model2,
-            effort,
-        } => cmd_compare(&config, &cache, &aliases, format, &model1, &model2, effort),
+            by_scaffold,
+        ),
+        Command::Compare { models, effort } => {
+            cmd_compare(&config, &cache, &aliases, format, &models, effort)
+        }
         Command::Watch {
             model,
             interval,
@@ -208,44 +360,180 @@ fn main() -> Result<()> {
             monitor::handle_command(subcommand, &config, &cache, &aliases)
         }
         Command::Sources => cmd_sources(&config, &cache, format),
+        Command::Correlate { tag } => {
+            cmd_correlate(&config, &cache, &aliases, tag.as_deref(), format)
+        }
+        Command::Pareto { quality, cost } => {
+            let results = fetch_all(&config, &cache);
+            let output = pareto::pareto(&results, &aliases, &quality, &cost)?;
+            println!("{}", pareto::render(&output, format)?);
+            Ok(())
+        }
+        Command::Cost {
+            input_tokens,
+            output_tokens,
+            cached_ratio,
+            quality,
+            top,
+        } => {
+            let defaults = TokenMix::from_config(&config.cost);
+            let mix = TokenMix {
+                input_tokens: input_tokens.unwrap_or(defaults.input_tokens),
+                output_tokens: output_tokens.unwrap_or(defaults.output_tokens),
+                cached_ratio: cached_ratio.unwrap_or(defaults.cached_ratio),
+            };
+            let results = fetch_all(&config, &cache);
+            let mut output = cost::estimate(&results, &aliases, mix, quality.as_ref())?;
+            if let Some(top) = top {
+                output.rows.truncate(top);
+            }
+            println!("{}", cost::render(&output, format)?);
+            Ok(())
+        }
+        Command::Outliers {
+            threshold,
+            min_sources,
+            tag,
+        } => {
+            let mut results = fetch_all(&config, &cache);
+            if let Some(tag_name) = tag.as_deref() {
+                retain_tag(&mut results, &config, tag_name)?;
+            }
+            let options = outliers::OutlierOptions {
+                threshold,
+                min_sources,
+            };
+            let output = outliers::find_outliers(&results, &aliases, &options);
+            println!("{}", outliers::render(&output, format)?);
+            Ok(())
+        }
+        Command::Explain {
+            model,
+            min_sources,
+            variant,
+            method,
+            coverage,
+            profile,
+            effort,
+            task,
+            scoring,
+        } => {
+            if coverage == Coverage::Impute && method == AggregateMethod::BradleyTerry {
+                anyhow::bail!("--coverage impute is not supported with --method bradley-terry");
+            }
+            let options = AggregateOptions {
+                min_sources,
+                show_excluded: false,
+                variant,
+                method,
+                coverage,
+                weights: profile
+                    .as_deref()
+                    .map(|name| aggregate_profile(&config, name))
+                    .transpose()?,
+            };
+            let canonical = aliases.resolve(&model);
+            let recommend = task
+                .as_deref()
+                .map(|task| {
+                    let options = recommend::RecommendOptions {
+                        top: 0,
+                        effort,
+                        latest_per_family: false,
+                        cost_mix: TokenMix::from_config(&config.cost),
+                        scoring,
+                        variant,
+                        constraints: Constraints::default(),
+                    };
+                    recommend::explain(&config, &cache, &aliases, task, &options, &canonical)
+                })
+                .transpose()?;
+
+            let mut results = fetch_all(&config, &cache);
+            apply_effort_filter(&mut results, effort, &aliases);
+            let output =
+                explain::explain(&results, &aliases, &options, &model, &canonical, recommend)?;
+            println!("{}", explain::render(&output, format)?);
+            Ok(())
+        }
         Command::Refresh => {
             cache.clear()?;
             eprintln!("Cache cleared. Re-fetching all sources...");
-            cmd_rank(
-                &config,
-                &cache,
-                &aliases,
-                format,
-                None,
-                None,
-                None,
-                None,
-                false,
-                None,
-                false,
-                None,
-                false,
-                AaEffortFilter::All,
-            )
+            cmd_rank(&config, &cache, &aliases, format, &RankArgs::default())
         }
         Command::Recommend {
             task,
             list_tasks,
+            all_tasks,
             top,
             effort,
+            latest_per_family,
+            scoring,
+            variant,
+            constraints,
         } => {
             if list_tasks {
-                println!("{}", recommend::list_tasks(format)?);
+                println!("{}", recommend::list_tasks(&config.recommend, format)?);
                 Ok(())
             } else {
-                let task = task.ok_or_else(|| {
-                    anyhow::anyhow!(
-                        "recommend requires a task. Use one of: coding, agentic, intelligence, general, cost"
-                    )
-                })?;
-                recommend::run(&config, &cache, &aliases, task, top, effort, format)
+                let options = recommend::RecommendOptions {
+                    top,
+                    effort,
+                    latest_per_family,
+                    cost_mix: TokenMix::from_config(&config.cost),
+                    scoring,
+                    variant,
+                    constraints,
+                };
+                if all_tasks {
+                    return recommend::run_all(&config, &cache, &aliases, &options, format);
+                }
+                let Some(task) = task else {
+                    let names: Vec<String> = recommend::task_specs(&config.recommend)?
+                        .into_iter()
+                        .map(|spec| spec.name)
+                        .collect();
+                    anyhow::bail!(
+                        "recommend requires a task or --all-tasks. Use one of: {}",
+                        names.join(", ")
+                    );
+                };
+                recommend::run(&config, &cache, &aliases, &task, &options, format)
             }
         }
+        Command::Advise {
+            models,
+            task,
+            exit_code,
+        } => {
+            let status = advise::run(&config, &cache, &aliases, task.as_deref(), &models, format)?;
+            if exit_code && status != 0 {
+                std::process::exit(status);
+            }
+            Ok(())
+        }
+        Command::ExportRouting {
+            tasks,
+            fallbacks,
+            effort,
+            scoring,
+            variant,
+            constraints,
+        } => {
+            let options = recommend::RecommendOptions {
+                top: 0,
+                effort,
+                latest_per_family: false,
+                cost_mix: TokenMix::from_config(&config.cost),
+                scoring,
+                variant,
+                constraints,
+            };
+            let format = routing::RoutingFormat::from_str(&cli.format)?;
+            routing::run(
+                &config, &cache, &aliases, &tasks, fallbacks, &options, format,
+            )
+        }
     }
 }
 
@@ -297,6 +585,22 @@ fn parse_source_tag(tag: &str) -> Option<SourceTag> {
     }
 }
 
+/// Keep only results from sources carrying `tag_name`.
+fn retain_tag(results: &mut Vec<SourceResult>, config: &Config, tag_name: &str) -> Result<()> {
+    let requested_tag = parse_source_tag(tag_name).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unknown tag: '{tag_name}'. Expected one of: reasoning, coding, agentic, general"
+        )
+    })?;
+    let tags_by_source = source_tag_map(config);
+    results.retain(|result| {
+        tags_by_source
+            .get(&result.source.to_lowercase())
+            .is_some_and(|tags| tags.contains(&requested_tag))
+    });
+    Ok(())
+}
+
 fn source_tag_name(tag: &SourceTag) -> &'static str {
     match tag {
         SourceTag::Reasoning => "reasoning",
@@ -309,23 +613,16 @@ fn source_tag_name(tag: &SourceTag) -> &'static str {
 fn cmd_rank(
     config: &Config,
     cache: &Cache,
-    _aliases: &AliasMap,
+    aliases: &AliasMap,
     format: OutputFormat,
-    top: Option<usize>,
-    source_filter: Option<&str>,
-    tag_filter: Option<&str>,
-    sources_filter: Option<&str>,
-    aggregate: bool,
-    min_sources: Option<usize>,
-    show_excluded: bool,
-    max_age: Option<u64>,
-    show_freshness: bool,
-    effort: AaEffortFilter,
+    args: &RankArgs,
 ) -> Result<()> {
     let mut results = fetch_all(config, cache);
+    ranking::apply_tie_mode(&mut results, args.ties);
     let now = Utc::now();
+    let top = args.top;
 
-    if let Some(max_age_days) = max_age {
+    if let Some(max_age_days) = args.max_age {
         let max_age_duration = Duration::days(max_age_days as i64);
         results.retain(|result| {
             let Some(fetched_at) = result.fetched_at else {
@@ -351,21 +648,11 @@ fn cmd_rank(
         });
     }
 
-    if let Some(tag_name) = tag_filter {
-        let requested_tag = parse_source_tag(tag_name).ok_or_else(|| {
-            anyhow::anyhow!(
-                "Unknown tag: '{tag_name}'. Expected one of: reasoning, coding, agentic, general"
-            )
-        })?;
-        let tags_by_source = source_tag_map(config);
-        results.retain(|result| {
-            tags_by_source
-                .get(&result.source.to_lowercase())
-                .is_some_and(|tags| tags.contains(&requested_tag))
-        });
+    if let Some(tag_name) = args.tag.as_deref() {
+        retain_tag(&mut results, config, tag_name)?;
     }
 
-    let merged_sources = sources_filter.or(source_filter);
+    let merged_sources = args.sources.as_deref().or(args.source.as_deref());
     if let Some(source_list) = merged_sources {
         let requested_sources: HashSet<String> = source_list
             .split(',')
@@ -395,7 +682,7 @@ fn cmd_rank(
         results = filtered;
     }
 
-    if show_freshness {
+    if args.show_freshness {
         eprintln!("Data freshness:");
         for result in &results {
             let freshness = match result.fetched_at {
@@ -422,17 +709,72 @@ fn cmd_rank(
         );
     }
 
-    apply_aa_effort_filter(&mut results, effort);
+    apply_effort_filter(&mut results, args.effort, aliases);
 
-    if aggregate {
-        let threshold = min_sources.unwrap_or(2);
+    if let Some(scaffold) = args.scaffold.as_deref() {
+        apply_scaffold_filter(&mut results, scaffold);
+    }
+    if args.best_harness {
+        keep_best_harness(&mut results, aliases);
+    }
+
+    if args.latest_per_family {
+        retain_latest_per_family(&mut results, aliases);
+    }
+
+    let show_excluded = args.show_excluded;
+    let mut weights = None;
+    if args.aggregate {
+        let threshold = args.min_sources.unwrap_or(2);
+        if args.coverage == Coverage::Impute && args.method == AggregateMethod::BradleyTerry {
+            anyhow::bail!("--coverage impute is not supported with --method bradley-terry");
+        }
+        let options = AggregateOptions {
+            min_sources: threshold,
+            show_excluded,
+            variant: args.variant,
+            method: args.method,
+            coverage: args.coverage,
+            weights: args
+                .profile
+                .as_deref()
+                .map(|name| aggregate_profile(config, name))
+                .transpose()?,
+        };
+        if options.weights.is_some() {
+            weights = Some(options.effective_weights(&results));
+        }
         let excluded_for_count = if show_excluded {
             Vec::new()
         } else {
-            excluded_models(results.as_slice(), threshold)
+            excluded_models(&results, aliases, &options)
         };
-        let (mut aggregated, excluded_models) =
-            aggregate_results(results, threshold, show_excluded);
+        let (mut aggregated, excluded_models) = aggregate_results(&results, aliases, &options);
+        if let Some(replicates) = args.bootstrap {
+            let bootstrap = BootstrapOptions {
+                replicates,
+                seed: args.seed,
+                top_k: args.top_k,
+            };
+            let models: Vec<String> = aggregated.scores.iter().map(|s| s.model.clone()).collect();
+            let sources = aggregate_sources(&results, aliases, &options);
+            let intervals = bootstrap_ranks(&sources, &models, &options, &bootstrap);
+            for score in &mut aggregated.scores {
+                let Some(interval) = intervals.get(&score.model) else {
+                    continue;
+                };
+                score
+                    .metrics
+                    .insert("rank_lo".into(), MetricValue::Int(interval.lo.into()));
+                score
+                    .metrics
+                    .insert("rank_hi".into(), MetricValue::Int(interval.hi.into()));
+                score.metrics.insert(
+                    format!("p_top_{}", args.top_k),
+                    MetricValue::Float(interval.p_top_k),
+                );
+            }
+        }
         let excluded_models = if show_excluded {
             excluded_models
         } else {
@@ -470,135 +812,72 @@ fn cmd_rank(
         },
         sources: results,
         source_tags: None,
+        weights,
     };
 
     println!("{}", output::render(&output, format)?);
     Ok(())
 }
 
-fn format_age(age: Duration) -> String {
-    let total_hours = age.num_hours().max(0);
-    let days = total_hours / 24;
-    let hours = total_hours % 24;
-    format!("{days}d {hours}h")
-}
-
-fn aggregate_results(
-    results: Vec<SourceResult>,
-    min_sources: usize,
-    show_excluded: bool,
-) -> (SourceResult, Vec<(String, usize)>) {
-    let mut totals: HashMap<String, Vec<f64>> = HashMap::new();
-
-    for source in results {
-        let total_in_source = source.scores.len();
-        if total_in_source == 0 {
-            continue;
-        }
-
-        for score in source.scores {
-            let Some(rank) = score.rank else {
-                continue;
-            };
-
-            let entry = totals.entry(score.model).or_default();
-            entry.push(percentile(rank, total_in_source));
-        }
+/// Drop rows for models superseded by a newer member of the same family that
+/// appears anywhere in `results`.
+pub fn retain_latest_per_family(results: &mut [SourceResult], aliases: &AliasMap) {
+    let present: HashSet<String> = results
+        .iter()
+        .flat_map(|result| result.scores.iter())
+        .map(|score| aliases.canonical_for(score))
+        .collect();
+    let superseded = aliases.superseded(&present);
+    if superseded.is_empty() {
+        return;
     }
 
-    let mut excluded: Vec<(String, usize)> = Vec::new();
-    let mut rows: Vec<(String, f64, f64, usize)> = totals
-        .into_iter()
-        .filter_map(|(model, percentiles)| {
-            let count = percentiles.len();
-            if count < min_sources {
-                if show_excluded {
-                    excluded.push((model, count));
-                }
-                None
-            } else {
-                let avg = percentiles.iter().sum::<f64>() / count as f64;
-                let spread = std_dev(&percentiles);
-                Some((model, avg, spread, count))
-            }
-        })
-        .collect();
+    for result in results.iter_mut() {
+        result
+            .scores
+            .retain(|score| !superseded.contains(&aliases.canonical_for(score)));
+    }
+}
 
-    rows.sort_by(|a, b| {
-        b.1.partial_cmp(&a.1)
-            .unwrap_or(Ordering::Equal)
-            .then_with(|| a.0.cmp(&b.0))
-    });
-    excluded.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+/// Source weights of a named `[aggregate.profiles.<name>]` table.
+fn aggregate_profile(config: &Config, name: &str) -> Result<HashMap<String, f64>> {
+    let Some(profile) = config.aggregate.profiles.get(name) else {
+        let mut available: Vec<&str> = config
+            .aggregate
+            .profiles
+            .keys()
+            .map(String::as_str)
+            .collect();
+        available.sort_unstable();
+        let available = if available.is_empty() {
+            "none configured".to_string()
+        } else {
+            available.join(", ")
+        };
+        anyhow::bail!("Unknown aggregate profile '{name}'. Available profiles: {available}");
+    };
 
-    let scores = rows
+    let known: HashSet<String> = get_sources()
         .into_iter()
-        .enumerate()
-        .map(
-            |(i, (model, avg_percentile, spread, sources_count))| ModelScore {
-                model: model.clone(),
-                source_model_name: model,
-                metrics: HashMap::from([
-                    (
-                        "avg_percentile".to_string(),
-                        MetricValue::Float(avg_percentile),
-                    ),
-                    ("spread".to_string(), MetricValue::Float(spread)),
-                    (
-                        "sources_count".to_string(),
-                        MetricValue::Int(sources_count as i64),
-                    ),
-                ]),
-                rank: Some((i + 1) as u32),
-            },
-        )
+        .map(|source| source.name().to_lowercase())
         .collect();
-
-    (
-        SourceResult {
-            source: "aggregate".to_string(),
-            fetched_at: None,
-            status: SourceStatus::Ok,
-            scores,
-        },
-        if show_excluded { excluded } else { Vec::new() },
-    )
-}
-
-fn excluded_models(results: &[SourceResult], min_sources: usize) -> Vec<(String, usize)> {
-    let mut counts: HashMap<String, usize> = HashMap::new();
-
-    for source in results {
-        for score in &source.scores {
-            if score.rank.is_none() {
-                continue;
-            }
-            *counts.entry(score.model.clone()).or_insert(0) += 1;
+    for (source, weight) in profile {
+        if !known.contains(&source.to_lowercase()) {
+            eprintln!("[profile {name}] unknown source '{source}' ignored");
+        }
+        if *weight < 0.0 {
+            anyhow::bail!("Profile '{name}': weight for '{source}' must not be negative");
         }
     }
 
-    let mut excluded: Vec<(String, usize)> = counts
-        .into_iter()
-        .filter(|(_, count)| *count < min_sources)
-        .collect();
-    excluded.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
-    excluded
-}
-
-fn percentile(rank: u32, total: usize) -> f64 {
-    if total <= 1 {
-        1.0
-    } else {
-        (total as f64 - rank as f64) / (total as f64 - 1.0)
-    }
+    Ok(profile.clone())
 }
 
-fn std_dev(values: &[f64]) -> f64 {
-    if values.len() < 2 {
-        return 0.0;
-    }
-    let mean = values.iter().sum::<f64>() / values.len() as f64;
-    (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64).sqrt()
+fn format_age(age: Duration) -> String {
+    let total_hours = age.num_hours().max(0);
+    let days = total_hours / 24;
+    let hours = total_hours % 24;
+    format!("{days}d {hours}h")
 }
 
 fn cmd_check(
@@ -608,8 +887,21 @@ fn cmd_check(
     format: OutputFormat,
     model: &str,
     show_matches: bool,
+    by_scaffold: bool,
 ) -> Result<()> {
     let canonical = aliases.resolve(model);
+    // A name that is not a known model but is a family expands to every member,
+    // so the output shows the progression across versions within each source.
+    let family_members = if canonical == model.to_lowercase() {
+        aliases.expand_family(&canonical)
+    } else {
+        Vec::new()
+    };
+    let members = if family_members.is_empty() {
+        vec![canonical.clone()]
+    } else {
+        family_members.clone()
+    };
     let results = fetch_all(config, cache);
 
     let mut match_lines = Vec::new();
@@ -617,12 +909,31 @@ fn cmd_check(
         .into_iter()
         .map(|mut r| {
             r.scores.retain(|s| {
-                s.model.to_lowercase() == canonical
-                    || aliases.matches(&s.source_model_name, &canonical)
+                (!by_scaffold || s.agent.is_some())
+                    && members.iter().any(|member| {
+                        s.model.to_lowercase() == *member
+                            || aliases.matches(&s.source_model_name, member)
+                    })
             });
+            if !family_members.is_empty() {
+                for s in &mut r.scores {
+                    s.model = aliases.canonical_for(s);
+                }
+                // Stable sort keeps each source's own ordering within a version.
+                r.scores.sort_by_key(|s| {
+                    members
+                        .iter()
+                        .position(|member| *member == s.model)
+                        .unwrap_or(members.len())
+                });
+            }
             if show_matches {
                 for s in &r.scores {
-                    match_lines.push(aliases.explain(&r.source, &s.source_model_name, &canonical));
+                    let target = members
+                        .iter()
+                        .find(|member| aliases.matches(&s.source_model_name, member))
+                        .unwrap_or(&canonical);
+                    match_lines.push(aliases.explain(&r.source, &s.source_model_name, target));
                 }
             }
             r
@@ -636,7 +947,7 @@ fn cmd_check(
                 m.source_name,
                 m.source_model_name,
                 m.canonical,
-                match_kind_str(&m.match_kind)
+                m.match_kind.as_str()
             );
         }
     }
@@ -654,11 +965,16 @@ fn cmd_check(
         query: QueryInfo {
             query_type: "check".into(),
             model: Some(canonical),
-            models: None,
+            models: if family_members.is_empty() {
+                None
+            } else {
+                Some(family_members)
+            },
             top: None,
         },
         sources: filtered,
         source_tags: None,
+        weights: None,
     };
 
     println!("{}", output::render(&output, format)?);
@@ -758,72 +1074,23 @@ fn cmd_watch(
     }
 }
 
-fn match_kind_str(kind: &MatchKind) -> &'static str {
-    match kind {
-        MatchKind::Exact => "exact",
-        MatchKind::Alias => "alias",
-        MatchKind::Prefix => "prefix",
-        MatchKind::NoMatch => "no-match",
-    }
-}
-
 fn cmd_compare(
     config: &Config,
     cache: &Cache,
     aliases: &AliasMap,
     format: OutputFormat,
-    model1: &str,
-    model2: &str,
-    effort: AaEffortFilter,
+    models: &[String],
+    effort: EffortFilter,
 ) -> Result<()> {
-    let c1 = aliases.resolve(model1);
-    let c2 = aliases.resolve(model2);
+    let canonicals: Vec<String> = models.iter().map(|model| aliases.resolve(model)).collect();
     let mut results = fetch_all(config, cache);
-    apply_aa_effort_filter(&mut results, effort);
+    apply_effort_filter(&mut results, effort, aliases);
 
-    let filtered: Vec<_> = results
-        .into_iter()
-        .map(|mut r| {
-            r.scores.retain(|s| {
-                let resolved = aliases.resolve(&s.source_model_name);
-                resolved == c1 || resolved == c2
-            });
-            r
-        })
-        .collect();
-
-    let output = PondusOutput {
-        timestamp: Utc::now(),
-        query: QueryInfo {
-            query_type: "compare".into(),
-            model: None,
-            models: Some(vec![c1, c2]),
-            top: None,
-        },
-        sources: filtered,
-        source_tags: None,
-    };
-
-    println!("{}", output::render(&output, format)?);
+    let output = compare::compare(&results, aliases, &canonicals)?;
+    println!("{}", compare::render(&output, format)?);
     Ok(())
 }
 
-fn apply_aa_effort_filter(results: &mut [SourceResult], effort: AaEffortFilter) {
-    if effort == AaEffortFilter::All {
-        return;
-    }
-
-    for result in results.iter_mut() {
-        if result.source != "artificial-analysis" {
-            continue;
-        }
-
-        result
-            .scores
-            .retain(|score| effort.matches(classify_effort_level(&score.source_model_name)));
-    }
-}
-
 fn cmd_sources(config: &Config, cache: &Cache, format: OutputFormat) -> Result<()> {
     let results = fetch_all(config, cache);
     let source_tags = source_tag_map(config)
@@ -848,169 +1115,33 @@ fn cmd_sources(config: &Config, cache: &Cache, format: OutputFormat) -> Result<(
         },
         sources: results,
         source_tags: Some(source_tags),
+        weights: None,
     };
 
     println!("{}", output::render(&output, format)?);
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::{
-        MetricValue, ModelScore, SourceResult, SourceStatus, SourceTag, aggregate_results,
-        parse_source_tag, percentile, std_dev,
-    };
-    use std::collections::HashMap;
-
-    fn make_source_with_ranked_model(
-        source: &str,
-        model: &str,
-        rank: u32,
-        total: usize,
-    ) -> SourceResult {
-        let mut scores = Vec::with_capacity(total);
-        scores.push(ModelScore {
-            model: model.to_string(),
-            source_model_name: model.to_string(),
-            metrics: HashMap::new(),
-            rank: Some(rank),
-        });
-
-        for i in 1..total {
-            let filler_rank = if i as u32 >= rank {
-                i as u32 + 1
-            } else {
-                i as u32
-            };
-            scores.push(ModelScore {
-                model: format!("filler-{source}-{i}"),
-                source_model_name: format!("filler-{source}-{i}"),
-                metrics: HashMap::new(),
-                rank: Some(filler_rank),
-            });
-        }
-
-        SourceResult {
-            source: source.to_string(),
-            fetched_at: None,
-            status: SourceStatus::Ok,
-            scores,
-        }
-    }
-
-    fn find_model<'a>(scores: &'a [ModelScore], model: &str) -> &'a ModelScore {
-        scores.iter().find(|s| s.model == model).unwrap()
-    }
-
-    fn metric_float(score: &ModelScore, name: &str) -> f64 {
-        match score.metrics.get(name).unwrap() {
-            MetricValue::Float(v) => *v,
-            _ => panic!("metric {name} is not float"),
-        }
-    }
-
-    fn metric_int(score: &ModelScore, name: &str) -> i64 {
-        match score.metrics.get(name).unwrap() {
-            MetricValue::Int(v) => *v,
-            _ => panic!("metric {name} is not int"),
-        }
-    }
-
-    #[test]
-    fn percentile_rank_1_of_10_is_1() {
-        assert!((percentile(1, 10) - 1.0).abs() < f64::EPSILON);
-    }
-
-    #[test]
-    fn percentile_rank_10_of_10_is_0() {
-        assert!((percentile(10, 10) - 0.0).abs() < f64::EPSILON);
-    }
-
-    #[test]
-    fn percentile_rank_1_of_1_is_1() {
-        assert!((percentile(1, 1) - 1.0).abs() < f64::EPSILON);
-    }
-
-    #[test]
-    fn percentile_rank_5_of_10_matches_expected_formula() {
-        let expected = (10.0 - 5.0) / (10.0 - 1.0);
-        assert!((percentile(5, 10) - expected).abs() < f64::EPSILON);
-    }
-
-    #[test]
-    fn two_sources_correct_average() {
-        let model = "model-a";
-        let source_a = make_source_with_ranked_model("source-a", model, 1, 10);
-        let source_b = make_source_with_ranked_model("source-b", model, 5, 10);
-
-        let (aggregated, excluded) = aggregate_results(vec![source_a, source_b], 2, false);
-        assert!(excluded.is_empty());
-
-        let score = find_model(&aggregated.scores, model);
-        let expected = (1.0 + (5.0 / 9.0)) / 2.0;
-        assert!((metric_float(score, "avg_percentile") - expected).abs() < 0.001);
-        assert_eq!(metric_int(score, "sources_count"), 2);
-    }
-
-    #[test]
-    fn min_sources_filters() {
-        let model_a = "model-a";
-        let model_b = "model-b";
-
-        let mut source_a = make_source_with_ranked_model("source-a", model_a, 1, 10);
-        source_a.scores[1] = ModelScore {
-            model: model_b.to_string(),
-            source_model_name: model_b.to_string(),
-            metrics: HashMap::new(),
-            rank: Some(2),
-        };
-        let source_b = make_source_with_ranked_model("source-b", model_a, 3, 10);
-
-        let (aggregated, excluded) = aggregate_results(vec![source_a, source_b], 2, true);
-
-        assert!(aggregated.scores.iter().all(|s| s.model != model_b));
-        assert!(excluded.iter().any(|(m, c)| m == model_b && *c == 1));
-    }
-
-    #[test]
-    fn spread_single_source_is_zero() {
-        let model = "model-a";
-        let source = make_source_with_ranked_model("source-a", model, 4, 10);
-
-        let (aggregated, _) = aggregate_results(vec![source], 1, false);
-        let score = find_model(&aggregated.scores, model);
-        assert!((metric_float(score, "spread") - 0.0).abs() < f64::EPSILON);
-    }
-
-    #[test]
-    fn spread_two_sources() {
-        let model = "model-a";
-        let source_a = make_source_with_ranked_model("source-a", model, 1, 10);
-        let source_b = make_source_with_ranked_model("source-b", model, 5, 10);
-
-        let (aggregated, _) = aggregate_results(vec![source_a, source_b], 2, false);
-        let score = find_model(&aggregated.scores, model);
-        let expected = 0.222;
-        assert!((metric_float(score, "spread") - expected).abs() < 0.001);
+fn cmd_correlate(
+    config: &Config,
+    cache: &Cache,
+    aliases: &AliasMap,
+    tag: Option<&str>,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut results = fetch_all(config, cache);
+    if let Some(tag_name) = tag {
+        retain_tag(&mut results, config, tag_name)?;
     }
 
-    #[test]
-    fn show_excluded_false_returns_empty() {
-        let model_a = "model-a";
-        let model_b = "model-b";
-
-        let mut source_a = make_source_with_ranked_model("source-a", model_a, 1, 10);
-        source_a.scores[1] = ModelScore {
-            model: model_b.to_string(),
-            source_model_name: model_b.to_string(),
-            metrics: HashMap::new(),
-            rank: Some(2),
-        };
-        let source_b = make_source_with_ranked_model("source-b", model_a, 3, 10);
+    let matrix = correlate::correlate(&results, aliases, tag);
+    println!("{}", correlate::render(&matrix, format)?);
+    Ok(())
+}
 
-        let (_, excluded) = aggregate_results(vec![source_a, source_b], 2, false);
-        assert!(excluded.is_empty());
-    }
+#[cfg(test)]
+mod tests {
+    use super::{SourceTag, parse_source_tag};
 
     #[test]
     fn parse_source_tag_valid() {
@@ -1041,10 +1172,4 @@ mod tests {
         assert!(parse_source_tag("unknown").is_none());
         assert!(parse_source_tag("").is_none());
     }
-
-    #[test]
-    fn std_dev_known_values() {
-        assert!((std_dev(&[1.0, 3.0]) - 1.0).abs() < f64::EPSILON);
-        assert!((std_dev(&[1.0]) - 0.0).abs() < f64::EPSILON);
-    }
 }
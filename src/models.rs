@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SourceResult {
@@ -32,10 +32,39 @@ pub enum SourceStatus {
 pub struct ModelScore {
     pub model: String,
     pub source_model_name: String,
+    /// Qualifiers parsed from `source_model_name` (effort, thinking, snapshot).
+    #[serde(default)]
+    pub variant: Variant,
+    /// Agent scaffold the model ran under (e.g. `OpenHands`, `Claude Code`) for
+    /// agentic sources that list harness + model combinations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
     pub metrics: HashMap<String, MetricValue>,
     pub rank: Option<u32>,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Effort {
+    Max,
+    #[default]
+    Standard,
+    Low,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Variant {
+    pub effort: Effort,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub thinking: bool,
+    /// Snapshot date as `YYYY-MM-DD`, e.g. from `claude-sonnet-4-5-20250929`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<String>,
+    /// Raw qualifier tokens in the order they appeared, e.g. `["thinking", "32k"]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub qualifiers: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum MetricValue {
@@ -51,6 +80,9 @@ pub struct PondusOutput {
     pub sources: Vec<SourceResult>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source_tags: Option<HashMap<String, Vec<String>>>,
+    /// Effective per-source weights of a weighted aggregate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<BTreeMap<String, f64>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -0,0 +1,316 @@
+use crate::aggregate::{ResolvedSource, resolve_sources};
+use crate::alias::AliasMap;
+use crate::models::SourceResult;
+use crate::output::{OutputFormat, padded_row};
+use crate::variant::VariantPolicy;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Sources listing fewer models than this have percentiles too coarse to judge
+/// a model by, so they neither get flagged nor count toward a consensus.
+const MIN_SOURCE_ROWS: usize = 3;
+
+#[derive(Debug, Clone, Copy)]
+pub struct OutlierOptions {
+    /// Smallest |percentile − consensus| that gets flagged
+    pub threshold: f64,
+    /// Only models listed by at least this many sources are checked
+    pub min_sources: usize,
+}
+
+/// Where one source places a model.
+#[derive(Debug, Clone, Serialize)]
+pub struct Placement {
+    pub source: String,
+    /// The name the source lists the model under
+    pub source_model_name: String,
+    pub rank: u32,
+    pub total: usize,
+    pub percentile: f64,
+    /// The source's primary metric, if it has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
+}
+
+/// A source that places a model far from where the other sources put it.
+#[derive(Debug, Serialize)]
+pub struct Outlier {
+    pub model: String,
+    pub source: String,
+    pub percentile: f64,
+    /// Mean percentile across the model's other sources
+    pub consensus: f64,
+    /// `percentile − consensus`: positive when this source rates the model higher
+    pub deviation: f64,
+    /// Every source's placement of the model, the flagged one included
+    pub placements: Vec<Placement>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OutliersOutput {
+    pub timestamp: DateTime<Utc>,
+    pub threshold: f64,
+    pub min_sources: usize,
+    pub outliers: Vec<Outlier>,
+}
+
+/// Flag every (model, source) pair whose percentile differs from the model's
+/// leave-one-out consensus by at least `options.threshold`, largest first.
+pub fn find_outliers(
+    results: &[SourceResult],
+    aliases: &AliasMap,
+    options: &OutlierOptions,
+) -> OutliersOutput {
+    let sources: Vec<ResolvedSource> = resolve_sources(results, aliases, VariantPolicy::Best)
+        .into_iter()
+        .filter(|source| source.total() >= MIN_SOURCE_ROWS)
+        .collect();
+
+    let mut by_model: BTreeMap<&str, Vec<Placement>> = BTreeMap::new();
+    for source in &sources {
+        for row in &source.rows {
+            by_model.entry(&row.model).or_default().push(Placement {
+                source: source.source.clone(),
+                source_model_name: row.source_model_name.clone(),
+                rank: row.rank,
+                total: source.total(),
+                percentile: source.percentile_of(row),
+                metric: source.metric,
+                value: source.metric.map(|_| row.value),
+            });
+        }
+    }
+
+    // A consensus needs at least two other sources
+    let min_sources = options.min_sources.max(3);
+    let mut outliers = Vec::new();
+    for (model, mut placements) in by_model {
+        if placements.len() < min_sources {
+            continue;
+        }
+        placements.sort_by(|a, b| a.source.cmp(&b.source));
+
+        let sum: f64 = placements.iter().map(|p| p.percentile).sum();
+        let others = (placements.len() - 1) as f64;
+        for placement in &placements {
+            let consensus = (sum - placement.percentile) / others;
+            let deviation = placement.percentile - consensus;
+            if deviation.abs() >= options.threshold {
+                outliers.push(Outlier {
+                    model: model.to_string(),
+                    source: placement.source.clone(),
+                    percentile: placement.percentile,
+                    consensus,
+                    deviation,
+                    placements: placements.clone(),
+                });
+            }
+        }
+    }
+    outliers.sort_by(|a, b| {
+        b.deviation
+            .abs()
+            .total_cmp(&a.deviation.abs())
+            .then_with(|| a.model.cmp(&b.model))
+            .then_with(|| a.source.cmp(&b.source))
+    });
+
+    OutliersOutput {
+        timestamp: Utc::now(),
+        threshold: options.threshold,
+        min_sources,
+        outliers,
+    }
+}
+
+pub fn render(output: &OutliersOutput, format: OutputFormat) -> Result<String> {
+    Ok(match format {
+        OutputFormat::Json => serde_json::to_string_pretty(output)?,
+        OutputFormat::Table => render_table(output),
+        OutputFormat::Markdown => render_markdown(output),
+    })
+}
+
+fn heading(outlier: &Outlier) -> String {
+    format!(
+        "{} on {}: percentile {:.2} vs {:.2} from the other sources ({:+.2})",
+        outlier.model, outlier.source, outlier.percentile, outlier.consensus, outlier.deviation
+    )
+}
+
+fn evidence_headers() -> Vec<String> {
+    ["Source", "Listed as", "Rank", "Percentile", "Value"]
+        .map(str::to_string)
+        .to_vec()
+}
+
+fn evidence_cells(outlier: &Outlier, placement: &Placement) -> Vec<String> {
+    let flag = if placement.source == outlier.source {
+        " *"
+    } else {
+        ""
+    };
+    let value = match (placement.metric, placement.value) {
+        (Some(metric), Some(value)) => format!("{value:.2} {metric}"),
+        _ => "—".to_string(),
+    };
+    vec![
+        format!("{}{flag}", placement.source),
+        placement.source_model_name.clone(),
+        format!("{}/{}", placement.rank, placement.total),
+        format!("{:.2}", placement.percentile),
+        value,
+    ]
+}
+
+fn summary(output: &OutliersOutput) -> String {
+    format!(
+        "{} flag(s) at |deviation| ≥ {} (models in {}+ sources)",
+        output.outliers.len(),
+        output.threshold,
+        output.min_sources
+    )
+}
+
+fn render_table(output: &OutliersOutput) -> String {
+    let mut lines = vec![summary(output)];
+
+    for outlier in &output.outliers {
+        lines.push(String::new());
+        lines.push(heading(outlier));
+
+        let headers = evidence_headers();
+        let rows: Vec<Vec<String>> = outlier
+            .placements
+            .iter()
+            .map(|placement| evidence_cells(outlier, placement))
+            .collect();
+        let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+        for row in &rows {
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = widths[i].max(cell.chars().count());
+            }
+        }
+        lines.push(format!("  {}", padded_row(&headers, &widths)));
+        for row in rows {
+            lines.push(format!("  {}", padded_row(&row, &widths)));
+        }
+    }
+    lines.join("\n")
+}
+
+fn render_markdown(output: &OutliersOutput) -> String {
+    let mut lines = vec![summary(output)];
+
+    for outlier in &output.outliers {
+        let headers = evidence_headers();
+        lines.push(String::new());
+        lines.push(format!("### {}", heading(outlier)));
+        lines.push(String::new());
+        lines.push(format!("| {} |", headers.join(" | ")));
+        lines.push(format!(
+            "| {} |",
+            headers
+                .iter()
+                .map(|_| "---")
+                .collect::<Vec<_>>()
+                .join(" | ")
+        ));
+        for placement in &outlier.placements {
+            let cells = evidence_cells(outlier, placement);
+            lines.push(format!("| {} |", cells.join(" | ").replace(" *", " \\*")));
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MetricValue, ModelScore, SourceStatus};
+    use crate::variant::parse_variant;
+    use std::collections::HashMap;
+
+    fn source(name: &str, metric: &str, models: &[&str]) -> SourceResult {
+        SourceResult {
+            source: name.to_string(),
+            fetched_at: None,
+            status: SourceStatus::Ok,
+            scores: models
+                .iter()
+                .enumerate()
+                .map(|(i, model)| ModelScore {
+                    model: model.to_string(),
+                    source_model_name: model.to_string(),
+                    variant: parse_variant(model),
+                    agent: None,
+                    metrics: HashMap::from([(
+                        metric.to_string(),
+                        MetricValue::Float((100 - i) as f64),
+                    )]),
+                    rank: Some((i + 1) as u32),
+                })
+                .collect(),
+        }
+    }
+
+    fn options(threshold: f64) -> OutlierOptions {
+        OutlierOptions {
+            threshold,
+            min_sources: 3,
+        }
+    }
+
+    #[test]
+    fn flags_the_source_that_disagrees() {
+        let results = vec![
+            source("arena", "elo_score", &["a", "b", "c", "d"]),
+            source("aider", "pass_rate_1", &["a", "b", "c", "d"]),
+            // swebench puts "a" last while everyone else has it first
+            source("swebench", "resolved_rate", &["b", "c", "d", "a"]),
+        ];
+
+        let output = find_outliers(&results, &AliasMap::bundled(), &options(0.6));
+        assert_eq!(output.outliers.len(), 1);
+        let outlier = &output.outliers[0];
+        assert_eq!(
+            (outlier.model.as_str(), outlier.source.as_str()),
+            ("a", "swebench")
+        );
+        assert!((outlier.percentile - 0.0).abs() < 1e-9);
+        assert!((outlier.consensus - 1.0).abs() < 1e-9);
+        assert!((outlier.deviation + 1.0).abs() < 1e-9);
+        assert_eq!(outlier.placements.len(), 3);
+        assert_eq!(outlier.placements[2].value, Some(97.0));
+        assert_eq!(outlier.placements[2].metric, Some("resolved_rate"));
+    }
+
+    #[test]
+    fn agreement_produces_no_flags() {
+        let models = ["a", "b", "c", "d"];
+        let results = vec![
+            source("arena", "elo_score", &models),
+            source("aider", "pass_rate_1", &models),
+            source("swebench", "resolved_rate", &models),
+        ];
+
+        let output = find_outliers(&results, &AliasMap::bundled(), &options(0.1));
+        assert!(output.outliers.is_empty());
+    }
+
+    #[test]
+    fn models_in_two_sources_are_not_checked() {
+        let results = vec![
+            source("arena", "elo_score", &["a", "b", "c"]),
+            source("aider", "pass_rate_1", &["c", "b", "a"]),
+        ];
+
+        let output = find_outliers(&results, &AliasMap::bundled(), &options(0.1));
+        assert!(output.outliers.is_empty());
+        assert_eq!(output.min_sources, 3);
+    }
+}
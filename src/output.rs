@@ -1,9 +1,8 @@
-use crate::models::{MetricValue, PondusOutput, SourceStatus};
-use crate::sources::aa::{AaEffort, classify_effort_level};
+use crate::models::{Effort, MetricValue, PondusOutput, SourceStatus};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use owo_colors::OwoColorize;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum OutputFormat {
@@ -50,6 +49,14 @@ fn render_json(output: &PondusOutput) -> Result<String> {
 fn render_table(output: &PondusOutput) -> Result<String> {
     let mut result = String::new();
 
+    if let Some(weights) = &output.weights {
+        result.push_str(&format!(
+            "{} {}\n\n",
+            "Weights:".bold(),
+            format_weights(weights)
+        ));
+    }
+
     for source in &output.sources {
         let status_str = format_status(&source.status);
         let header = format!("{} [{}]", source.source.bold(), status_str);
@@ -71,7 +78,11 @@ fn render_table(output: &PondusOutput) -> Result<String> {
         let mut all_metrics = all_metrics;
         all_metrics.sort();
 
+        let has_agents = source.scores.iter().any(|s| s.agent.is_some());
         let mut columns: Vec<String> = vec!["Rank".to_string(), "Model".to_string()];
+        if has_agents {
+            columns.push("Agent".to_string());
+        }
         columns.extend(all_metrics.clone());
 
         let mut widths: Vec<usize> = columns.iter().map(|c| c.len()).collect();
@@ -83,6 +94,9 @@ fn render_table(output: &PondusOutput) -> Result<String> {
                 .map(|r| r.to_string())
                 .unwrap_or_else(|| "-".to_string());
             let mut row = vec![rank, score.model.clone()];
+            if has_agents {
+                row.push(score.agent.clone().unwrap_or_else(|| "-".to_string()));
+            }
             for metric in &all_metrics {
                 let val = score
                     .metrics
@@ -134,7 +148,7 @@ fn render_table(output: &PondusOutput) -> Result<String> {
         if source.source == "artificial-analysis" && aa_has_mixed_effort_variants(source) {
             result.push('\n');
             result.push_str(
-                "  AA effort: (max) = Adaptive Reasoning Max Effort · standard = Non-reasoning High Effort · (low) = Non-reasoning Low Effort\n",
+                "  AA effort: max = Max/High Effort, Thinking or Reasoning · standard = Non-reasoning High Effort · low = Low Effort\n",
             );
         }
 
@@ -208,6 +222,10 @@ fn render_sources_table(output: &PondusOutput) -> Result<String> {
 fn render_markdown(output: &PondusOutput) -> Result<String> {
     let mut result = String::new();
 
+    if let Some(weights) = &output.weights {
+        result.push_str(&format!("Weights: {}\n\n", format_weights(weights)));
+    }
+
     for source in &output.sources {
         result.push_str(&format!("## {}\n\n", source.source));
 
@@ -234,7 +252,11 @@ fn render_markdown(output: &PondusOutput) -> Result<String> {
         let mut all_metrics = all_metrics;
         all_metrics.sort();
 
+        let has_agents = source.scores.iter().any(|s| s.agent.is_some());
         let mut columns: Vec<String> = vec!["Rank".to_string(), "Model".to_string()];
+        if has_agents {
+            columns.push("Agent".to_string());
+        }
         columns.extend(all_metrics.clone());
 
         let header: String = columns.to_vec().join(" | ");
@@ -253,6 +275,9 @@ fn render_markdown(output: &PondusOutput) -> Result<String> {
                 .map(|r| r.to_string())
                 .unwrap_or_else(|| "-".to_string());
             let mut row = vec![rank, score.model.clone()];
+            if has_agents {
+                row.push(score.agent.clone().unwrap_or_else(|| "-".to_string()));
+            }
             for metric in &all_metrics {
                 let val = score
                     .metrics
@@ -314,7 +339,12 @@ fn format_status(status: &SourceStatus) -> String {
 fn format_metric(metric_name: &str, value: &MetricValue) -> String {
     match value {
         MetricValue::Float(f) => {
-            if metric_name == "avg_percentile" || metric_name == "spread" {
+            if metric_name == "avg_percentile"
+                || metric_name == "spread"
+                || metric_name == "coverage"
+                || metric_name.starts_with("contrib.")
+                || metric_name.starts_with("p_top_")
+            {
                 format!("{:.3}", f)
             } else {
                 format!("{:.2}", f)
@@ -325,6 +355,14 @@ fn format_metric(metric_name: &str, value: &MetricValue) -> String {
     }
 }
 
+fn format_weights(weights: &BTreeMap<String, f64>) -> String {
+    weights
+        .iter()
+        .map(|(source, weight)| format!("{source}={weight}"))
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
 fn format_cached_age(fetched_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> String {
     match fetched_at {
         Some(ts) => {
@@ -341,12 +379,22 @@ fn pad(s: &str, width: usize) -> String {
     format!("{:width$}", s, width = width)
 }
 
+/// Cells left-aligned to `widths`, counted in chars, joined by two spaces.
+pub(crate) fn padded_row(values: &[String], widths: &[usize]) -> String {
+    values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| pad(value, widths[index]))
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
 fn aa_has_mixed_effort_variants(source: &crate::models::SourceResult) -> bool {
     source
         .scores
         .iter()
-        .map(|score| classify_effort_level(&score.source_model_name))
-        .collect::<HashSet<AaEffort>>()
+        .map(|score| score.variant.effort)
+        .collect::<HashSet<Effort>>()
         .len()
         > 1
 }
@@ -0,0 +1,544 @@
+use crate::aggregate::metric_as_f64;
+use crate::alias::AliasMap;
+use crate::models::{ModelScore, SourceResult};
+use crate::output::{OutputFormat, padded_row};
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Input:output token mix behind `openrouter.blended` (3 prompt tokens per completion token).
+const BLEND_INPUT: f64 = 3.0;
+const BLEND_OUTPUT: f64 = 1.0;
+
+const PLOT_WIDTH: usize = 60;
+const PLOT_HEIGHT: usize = 16;
+
+/// A `source.metric` reference such as `swebench.resolved_rate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricRef {
+    pub source: String,
+    pub metric: String,
+}
+
+impl FromStr for MetricRef {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.split_once('.') {
+            Some((source, metric)) if !source.is_empty() && !metric.is_empty() => Ok(Self {
+                source: source.trim().to_lowercase(),
+                metric: metric.trim().to_string(),
+            }),
+            _ => bail!("Expected <source>.<metric>, e.g. swebench.resolved_rate; got '{value}'"),
+        }
+    }
+}
+
+impl fmt::Display for MetricRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.source, self.metric)
+    }
+}
+
+impl Serialize for MetricRef {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParetoPoint {
+    pub model: String,
+    pub quality: f64,
+    pub cost: f64,
+}
+
+/// A model some other model beats on quality without costing more (or vice versa).
+#[derive(Debug, Clone, Serialize)]
+pub struct DominatedPoint {
+    pub model: String,
+    pub quality: f64,
+    pub cost: f64,
+    /// The highest-quality model that dominates this one
+    pub dominated_by: String,
+    pub dominator_quality: f64,
+    pub dominator_cost: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ParetoOutput {
+    pub timestamp: DateTime<Utc>,
+    pub quality: MetricRef,
+    pub cost: MetricRef,
+    /// Non-dominated models, cheapest first
+    pub frontier: Vec<ParetoPoint>,
+    /// Dominated models, best quality first
+    pub dominated: Vec<DominatedPoint>,
+}
+
+/// Join `quality` and `cost` on canonical model name and split the models into
+/// the cost–quality frontier and the models it dominates.
+///
+/// Where a source lists a model more than once, the best quality and the lowest
+/// cost are kept.
+pub fn pareto(
+    results: &[SourceResult],
+    aliases: &AliasMap,
+    quality: &MetricRef,
+    cost: &MetricRef,
+) -> Result<ParetoOutput> {
+    let qualities = metric_by_model(results, aliases, quality, Ordering::Greater)?;
+    let costs = metric_by_model(results, aliases, cost, Ordering::Less)?;
+
+    let mut points: Vec<ParetoPoint> = qualities
+        .into_iter()
+        .filter_map(|(model, quality)| {
+            costs.get(&model).map(|cost| ParetoPoint {
+                model,
+                quality,
+                cost: *cost,
+            })
+        })
+        .collect();
+    if points.is_empty() {
+        bail!("No model has both {quality} and {cost}");
+    }
+    points.sort_by(|a, b| {
+        a.cost
+            .total_cmp(&b.cost)
+            .then_with(|| b.quality.total_cmp(&a.quality))
+            .then_with(|| a.model.cmp(&b.model))
+    });
+
+    let mut frontier = Vec::new();
+    let mut dominated = Vec::new();
+    for point in &points {
+        let best_dominator = points
+            .iter()
+            .filter(|other| dominates(other, point))
+            .max_by(|a, b| {
+                a.quality
+                    .total_cmp(&b.quality)
+                    .then_with(|| b.cost.total_cmp(&a.cost))
+                    .then_with(|| b.model.cmp(&a.model))
+            });
+        match best_dominator {
+            Some(dominator) => dominated.push(DominatedPoint {
+                model: point.model.clone(),
+                quality: point.quality,
+                cost: point.cost,
+                dominated_by: dominator.model.clone(),
+                dominator_quality: dominator.quality,
+                dominator_cost: dominator.cost,
+            }),
+            None => frontier.push(point.clone()),
+        }
+    }
+    dominated.sort_by(|a, b| {
+        b.quality
+            .total_cmp(&a.quality)
+            .then_with(|| a.cost.total_cmp(&b.cost))
+            .then_with(|| a.model.cmp(&b.model))
+    });
+
+    Ok(ParetoOutput {
+        timestamp: Utc::now(),
+        quality: quality.clone(),
+        cost: cost.clone(),
+        frontier,
+        dominated,
+    })
+}
+
+pub fn render(output: &ParetoOutput, format: OutputFormat) -> Result<String> {
+    Ok(match format {
+        OutputFormat::Json => serde_json::to_string_pretty(output)?,
+        OutputFormat::Table => render_table(output),
+        OutputFormat::Markdown => render_markdown(output),
+    })
+}
+
+/// At least as good on both axes and strictly better on one.
+fn dominates(a: &ParetoPoint, b: &ParetoPoint) -> bool {
+    a.quality >= b.quality && a.cost <= b.cost && (a.quality > b.quality || a.cost < b.cost)
+}
+
+/// One value per canonical model, keeping the row that compares as `keep`
+/// (`Greater` for quality, `Less` for cost).
+pub fn metric_by_model(
+    results: &[SourceResult],
+    aliases: &AliasMap,
+    metric: &MetricRef,
+    keep: Ordering,
+) -> Result<HashMap<String, f64>> {
+    let Some(result) = results
+        .iter()
+        .find(|result| result.source.eq_ignore_ascii_case(&metric.source))
+    else {
+        let available: Vec<&str> = results.iter().map(|r| r.source.as_str()).collect();
+        bail!(
+            "Unknown source '{}'. Available: {}",
+            metric.source,
+            available.join(", ")
+        );
+    };
+
+    let mut values: HashMap<String, f64> = HashMap::new();
+    for score in &result.scores {
+        let Some(value) = metric_value(score, &metric.metric) else {
+            continue;
+        };
+        let model = aliases.canonical_for(score);
+        match values.get(&model) {
+            Some(existing) if value.total_cmp(existing) != keep => {}
+            _ => {
+                values.insert(model, value);
+            }
+        }
+    }
+
+    if values.is_empty() && !result.scores.is_empty() {
+        bail!("No {} rows report '{}'", metric.source, metric.metric);
+    }
+    Ok(values)
+}
+
+/// A metric from `score`, including the derived `blended` price per 1M tokens.
+pub fn metric_value(score: &ModelScore, metric: &str) -> Option<f64> {
+    if metric == "blended" {
+        let prompt = metric_f64(score, "prompt_per_1m")?;
+        let completion = metric_f64(score, "completion_per_1m")?;
+        return Some(
+            (prompt * BLEND_INPUT + completion * BLEND_OUTPUT) / (BLEND_INPUT + BLEND_OUTPUT),
+        );
+    }
+    metric_f64(score, metric)
+}
+
+/// `metric` on `score` as a number; text metrics have none.
+pub(crate) fn metric_f64(score: &ModelScore, metric: &str) -> Option<f64> {
+    score.metrics.get(metric).and_then(metric_as_f64)
+}
+
+fn format_value(value: f64) -> String {
+    if value.abs() >= 100.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+/// Plot marker for the i-th frontier model: A–Z, then a–z.
+fn marker(index: usize) -> char {
+    match index {
+        0..26 => (b'A' + index as u8) as char,
+        26..52 => (b'a' + (index - 26) as u8) as char,
+        _ => '*',
+    }
+}
+
+fn render_table(output: &ParetoOutput) -> String {
+    let mut lines = vec![
+        format!("Quality: {} (higher is better)", output.quality),
+        format!("Cost:    {} (lower is better)", output.cost),
+        String::new(),
+        "Frontier".to_string(),
+    ];
+
+    let headers = ["", "Model", "Quality", "Cost"].map(str::to_string);
+    let rows: Vec<Vec<String>> = output
+        .frontier
+        .iter()
+        .enumerate()
+        .map(|(i, point)| {
+            vec![
+                marker(i).to_string(),
+                point.model.clone(),
+                format_value(point.quality),
+                format_value(point.cost),
+            ]
+        })
+        .collect();
+    push_table(&mut lines, &headers, &rows);
+
+    if !output.dominated.is_empty() {
+        lines.push(String::new());
+        lines.push("Dominated".to_string());
+        let headers = [
+            "Model",
+            "Quality",
+            "Cost",
+            "Better option",
+            "Quality",
+            "Cost",
+        ]
+        .map(str::to_string);
+        let rows: Vec<Vec<String>> = output
+            .dominated
+            .iter()
+            .map(|point| {
+                vec![
+                    point.model.clone(),
+                    format_value(point.quality),
+                    format_value(point.cost),
+                    point.dominated_by.clone(),
+                    format_value(point.dominator_quality),
+                    format_value(point.dominator_cost),
+                ]
+            })
+            .collect();
+        push_table(&mut lines, &headers, &rows);
+    }
+
+    lines.push(String::new());
+    lines.extend(scatter(output));
+    lines.join("\n")
+}
+
+fn push_table(lines: &mut Vec<String>, headers: &[String], rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+    lines.push(padded_row(headers, &widths));
+    lines.push(
+        widths
+            .iter()
+            .map(|w| "-".repeat(*w))
+            .collect::<Vec<_>>()
+            .join("  "),
+    );
+    for row in rows {
+        lines.push(padded_row(row, &widths));
+    }
+}
+
+/// Quality against cost, frontier models lettered as in the table and dominated
+/// models drawn as dots. Cost uses a log scale when it spans more than 10x.
+fn scatter(output: &ParetoOutput) -> Vec<String> {
+    let frontier = output.frontier.iter().map(|p| (p.quality, p.cost));
+    let dominated = output.dominated.iter().map(|p| (p.quality, p.cost));
+    let all: Vec<(f64, f64)> = frontier.clone().chain(dominated.clone()).collect();
+
+    let (q_min, q_max) = bounds(all.iter().map(|p| p.0));
+    let (c_min, c_max) = bounds(all.iter().map(|p| p.1));
+    let log_cost = c_min > 0.0 && c_max / c_min > 10.0;
+    let scale_cost = |cost: f64| if log_cost { cost.ln() } else { cost };
+    let (x_min, x_max) = (scale_cost(c_min), scale_cost(c_max));
+
+    let column = |cost: f64| position(scale_cost(cost), x_min, x_max, PLOT_WIDTH);
+    let row = |quality: f64| PLOT_HEIGHT - 1 - position(quality, q_min, q_max, PLOT_HEIGHT);
+
+    let mut grid = vec![vec![' '; PLOT_WIDTH]; PLOT_HEIGHT];
+    for (quality, cost) in dominated {
+        let cell = &mut grid[row(quality)][column(cost)];
+        if *cell == ' ' {
+            *cell = '·';
+        }
+    }
+    for (i, (quality, cost)) in frontier.enumerate() {
+        grid[row(quality)][column(cost)] = marker(i);
+    }
+
+    let q_top = format_value(q_max);
+    let q_bottom = format_value(q_min);
+    let label_width = q_top.len().max(q_bottom.len());
+    let mut lines = Vec::with_capacity(PLOT_HEIGHT + 3);
+    for (i, cells) in grid.iter().enumerate() {
+        let label = match i {
+            0 => q_top.as_str(),
+            _ if i == PLOT_HEIGHT - 1 => q_bottom.as_str(),
+            _ => "",
+        };
+        let cells: String = cells.iter().collect();
+        lines.push(format!("{label:>label_width$} │{}", cells.trim_end()));
+    }
+    lines.push(format!(
+        "{} └{}",
+        " ".repeat(label_width),
+        "─".repeat(PLOT_WIDTH)
+    ));
+
+    let c_left = format_value(c_min);
+    let c_right = format_value(c_max);
+    let gap = PLOT_WIDTH.saturating_sub(c_left.len() + c_right.len());
+    lines.push(format!(
+        "{}  {c_left}{}{c_right}",
+        " ".repeat(label_width),
+        " ".repeat(gap)
+    ));
+    lines.push(format!(
+        "{}  cost{}",
+        " ".repeat(label_width),
+        if log_cost { " (log scale)" } else { "" }
+    ));
+    lines
+}
+
+fn bounds(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+        (lo.min(v), hi.max(v))
+    })
+}
+
+/// Cell index of `value` on an axis of `cells` cells spanning `min..=max`.
+fn position(value: f64, min: f64, max: f64, cells: usize) -> usize {
+    if max <= min {
+        return cells / 2;
+    }
+    let fraction = (value - min) / (max - min);
+    ((fraction * (cells - 1) as f64).round() as usize).min(cells - 1)
+}
+
+fn render_markdown(output: &ParetoOutput) -> String {
+    let mut lines = vec![
+        format!(
+            "**Quality:** `{}` (higher is better)  \n**Cost:** `{}` (lower is better)",
+            output.quality, output.cost
+        ),
+        String::new(),
+        "### Frontier".to_string(),
+        String::new(),
+        "| Model | Quality | Cost |".to_string(),
+        "| --- | --- | --- |".to_string(),
+    ];
+    for point in &output.frontier {
+        lines.push(format!(
+            "| {} | {} | {} |",
+            point.model,
+            format_value(point.quality),
+            format_value(point.cost)
+        ));
+    }
+
+    if !output.dominated.is_empty() {
+        lines.push(String::new());
+        lines.push("### Dominated".to_string());
+        lines.push(String::new());
+        lines.push("| Model | Quality | Cost | Better option | Quality | Cost |".to_string());
+        lines.push("| --- | --- | --- | --- | --- | --- |".to_string());
+        for point in &output.dominated {
+            lines.push(format!(
+                "| {} | {} | {} | {} | {} | {} |",
+                point.model,
+                format_value(point.quality),
+                format_value(point.cost),
+                point.dominated_by,
+                format_value(point.dominator_quality),
+                format_value(point.dominator_cost)
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MetricValue, SourceStatus};
+    use crate::variant::parse_variant;
+
+    fn source(name: &str, rows: &[(&str, &[(&str, f64)])]) -> SourceResult {
+        SourceResult {
+            source: name.to_string(),
+            fetched_at: None,
+            status: SourceStatus::Ok,
+            scores: rows
+                .iter()
+                .map(|(model, metrics)| ModelScore {
+                    model: model.to_string(),
+                    source_model_name: model.to_string(),
+                    variant: parse_variant(model),
+                    agent: None,
+                    metrics: metrics
+                        .iter()
+                        .map(|(name, value)| (name.to_string(), MetricValue::Float(*value)))
+                        .collect(),
+                    rank: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn metric(value: &str) -> MetricRef {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn metric_ref_parses_source_and_metric() {
+        assert_eq!(
+            metric("swebench.resolved_rate"),
+            MetricRef {
+                source: "swebench".into(),
+                metric: "resolved_rate".into()
+            }
+        );
+        assert!("swebench".parse::<MetricRef>().is_err());
+        assert!(".cost".parse::<MetricRef>().is_err());
+    }
+
+    #[test]
+    fn blended_price_weights_input_three_to_one() {
+        let result = source(
+            "openrouter",
+            &[("x", &[("prompt_per_1m", 1.0), ("completion_per_1m", 5.0)])],
+        );
+        assert_eq!(metric_value(&result.scores[0], "blended"), Some(2.0));
+    }
+
+    #[test]
+    fn splits_frontier_from_dominated_models() {
+        let quality = source(
+            "swebench",
+            &[
+                ("cheap", &[("resolved_rate", 50.0)]),
+                ("mid", &[("resolved_rate", 70.0)]),
+                ("best", &[("resolved_rate", 80.0)]),
+                ("overpriced", &[("resolved_rate", 60.0)]),
+                ("no-price", &[("resolved_rate", 99.0)]),
+            ],
+        );
+        let cost = source(
+            "aider",
+            &[
+                ("cheap", &[("cost", 1.0)]),
+                ("mid", &[("cost", 5.0)]),
+                ("best", &[("cost", 20.0)]),
+                ("overpriced", &[("cost", 10.0)]),
+            ],
+        );
+
+        let output = pareto(
+            &[quality, cost],
+            &AliasMap::bundled(),
+            &metric("swebench.resolved_rate"),
+            &metric("aider.cost"),
+        )
+        .unwrap();
+
+        let frontier: Vec<&str> = output.frontier.iter().map(|p| p.model.as_str()).collect();
+        assert_eq!(frontier, vec!["cheap", "mid", "best"]);
+        assert_eq!(output.dominated.len(), 1);
+        assert_eq!(output.dominated[0].model, "overpriced");
+        assert_eq!(output.dominated[0].dominated_by, "mid");
+    }
+
+    #[test]
+    fn unknown_source_is_an_error() {
+        let quality = source("swebench", &[("x", &[("resolved_rate", 50.0)])]);
+        let err = pareto(
+            &[quality],
+            &AliasMap::bundled(),
+            &metric("swebench.resolved_rate"),
+            &metric("nope.cost"),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Unknown source 'nope'"));
+    }
+}
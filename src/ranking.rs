@@ -0,0 +1,163 @@
+use crate::models::{MetricValue, ModelScore, SourceResult};
+use clap::ValueEnum;
+use std::cmp::Ordering;
+
+/// How equal scores within a source are numbered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum TieMode {
+    /// Ties share the best position and the ranks after them are skipped (1, 2, 2, 4)
+    #[default]
+    Competition,
+    /// Ties share a rank and the next rank follows on directly (1, 2, 2, 3)
+    Dense,
+}
+
+/// Competition ranks for `values` sorted best (highest) first: equal values
+/// share the position of the first of them.
+pub fn competition_ranks(values: impl IntoIterator<Item = f64>) -> Vec<u32> {
+    let mut ranks: Vec<u32> = Vec::new();
+    let mut previous: Option<f64> = None;
+    for (i, value) in values.into_iter().enumerate() {
+        let rank = match (previous, ranks.last()) {
+            (Some(prev), Some(last)) if prev == value => *last,
+            _ => (i + 1) as u32,
+        };
+        ranks.push(rank);
+        previous = Some(value);
+    }
+    ranks
+}
+
+/// Sort `scores` by `metric`, highest first, and assign competition ranks.
+/// Rows missing the metric count as 0; equal rows are ordered by the name the
+/// source uses, so the order no longer depends on how rows were collected.
+pub fn rank_scores(scores: &mut [ModelScore], metric: &str) {
+    let value = |score: &ModelScore| match score.metrics.get(metric) {
+        Some(MetricValue::Float(v)) => *v,
+        Some(MetricValue::Int(v)) => *v as f64,
+        _ => 0.0,
+    };
+    scores.sort_by(|a, b| {
+        value(b)
+            .total_cmp(&value(a))
+            .then_with(|| a.source_model_name.cmp(&b.source_model_name))
+    });
+
+    let ranks = competition_ranks(scores.iter().map(value));
+    for (score, rank) in scores.iter_mut().zip(ranks) {
+        score.rank = Some(rank);
+    }
+}
+
+/// Renumber every source's competition ranks under `mode`. Sources always
+/// produce competition ranks, so only `Dense` changes anything. A `rank`
+/// metric, where a source reports one, is kept in step.
+pub fn apply_tie_mode(results: &mut [SourceResult], mode: TieMode) {
+    if mode == TieMode::Competition {
+        return;
+    }
+
+    for result in results {
+        let mut distinct: Vec<u32> = result.scores.iter().filter_map(|s| s.rank).collect();
+        distinct.sort_unstable();
+        distinct.dedup();
+
+        for score in &mut result.scores {
+            let Some(rank) = score.rank else {
+                continue;
+            };
+            let dense = distinct.partition_point(|r| *r < rank) as u32 + 1;
+            score.rank = Some(dense);
+            if let Some(MetricValue::Int(_)) = score.metrics.get("rank") {
+                score
+                    .metrics
+                    .insert("rank".into(), MetricValue::Int(dense as i64));
+            }
+        }
+    }
+}
+
+/// Arena-style upper-bound ranks from `(score, ci)` pairs: one plus the number
+/// of models whose lower bound clears this model's upper bound. Models whose
+/// intervals overlap can share a rank, which marks them as statistically tied.
+pub fn upper_bound_ranks(rows: &[(f64, f64)]) -> Vec<u32> {
+    rows.iter()
+        .map(|(score, ci)| {
+            let upper = score + ci;
+            let clearly_better = rows
+                .iter()
+                .filter(|(other, other_ci)| {
+                    (other - other_ci).partial_cmp(&upper) == Some(Ordering::Greater)
+                })
+                .count();
+            clearly_better as u32 + 1
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SourceStatus;
+    use crate::variant::parse_variant;
+    use std::collections::HashMap;
+
+    fn score(name: &str, value: i64) -> ModelScore {
+        ModelScore {
+            model: name.to_string(),
+            source_model_name: name.to_string(),
+            variant: parse_variant(name),
+            agent: None,
+            metrics: HashMap::from([("tasks_completed".into(), MetricValue::Int(value))]),
+            rank: None,
+        }
+    }
+
+    #[test]
+    fn equal_values_share_a_competition_rank() {
+        assert_eq!(competition_ranks([9.0, 7.0, 7.0, 5.0]), vec![1, 2, 2, 4]);
+        assert_eq!(competition_ranks([1.0, 1.0, 1.0]), vec![1, 1, 1]);
+        assert!(competition_ranks([]).is_empty());
+    }
+
+    #[test]
+    fn rank_scores_breaks_ties_by_name() {
+        let mut scores = vec![score("zeta", 40), score("alpha", 40), score("top", 50)];
+        rank_scores(&mut scores, "tasks_completed");
+
+        let order: Vec<(&str, Option<u32>)> =
+            scores.iter().map(|s| (s.model.as_str(), s.rank)).collect();
+        assert_eq!(
+            order,
+            vec![("top", Some(1)), ("alpha", Some(2)), ("zeta", Some(2))]
+        );
+    }
+
+    #[test]
+    fn dense_mode_closes_gaps_after_ties() {
+        let mut scores = vec![score("a", 9), score("b", 7), score("c", 7), score("d", 5)];
+        rank_scores(&mut scores, "tasks_completed");
+        scores[3].metrics.insert("rank".into(), MetricValue::Int(4));
+        let mut results = vec![SourceResult {
+            source: "terminal-bench".into(),
+            fetched_at: None,
+            status: SourceStatus::Ok,
+            scores,
+        }];
+
+        apply_tie_mode(&mut results, TieMode::Dense);
+        let ranks: Vec<Option<u32>> = results[0].scores.iter().map(|s| s.rank).collect();
+        assert_eq!(ranks, vec![Some(1), Some(2), Some(2), Some(3)]);
+        assert!(matches!(
+            results[0].scores[3].metrics.get("rank"),
+            Some(MetricValue::Int(3))
+        ));
+    }
+
+    #[test]
+    fn overlapping_intervals_share_an_upper_bound_rank() {
+        // 1500±5 is clear of 1480±5; 1478±10 overlaps 1480±5
+        let ranks = upper_bound_ranks(&[(1500.0, 5.0), (1480.0, 5.0), (1478.0, 10.0)]);
+        assert_eq!(ranks, vec![1, 2, 2]);
+    }
+}
@@ -1,69 +1,122 @@
+use crate::aggregate::percentile;
 use crate::alias::AliasMap;
 use crate::cache::Cache;
-use crate::config::Config;
-use crate::models::{MetricValue, ModelScore, SourceResult, SourceStatus};
-use crate::output::OutputFormat;
+use crate::config::{Config, RecommendConfig, TaskConfig};
+use crate::cost::{TokenMix, format_tokens, parse_token_count};
+use crate::models::{Effort, MetricValue, ModelScore, SourceResult, SourceStatus};
+use crate::output::{OutputFormat, padded_row};
+use crate::pareto::metric_value;
 use crate::sources::Source;
-use crate::sources::aa::{AaEffortFilter, classify_effort_level};
 use crate::sources::{self};
-use anyhow::{Result, anyhow};
+use crate::variant::{EffortFilter, VariantPolicy, apply_effort_filter};
+use anyhow::{Result, anyhow, bail};
 use chrono::Utc;
-use clap::ValueEnum;
-use serde::Serialize;
+use clap::{Args, ValueEnum};
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+/// Which way a task metric sorts: `desc` when higher is better.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    #[default]
+    Desc,
+    Asc,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceMetricSpec {
+    pub source: String,
+    pub label: String,
+    pub metric: String,
+    pub sort: SortDirection,
+    /// Relative weight of the source within its task
+    pub weight: f64,
+}
+
+/// Where a task definition comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "snake_case")]
-pub enum RecommendTask {
-    Coding,
-    Agentic,
-    Intelligence,
-    General,
-    Cost,
+pub enum TaskOrigin {
+    BuiltIn,
+    /// `[recommend.tasks.<name>]`, which replaces a built-in of the same name
+    Config,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum SortDirection {
-    Desc,
-    Asc,
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskSpec {
+    #[serde(rename = "task")]
+    pub name: String,
+    pub description: String,
+    pub origin: TaskOrigin,
+    /// The first source is the task's primary signal
+    pub sources: Vec<SourceMetricSpec>,
+}
+
+impl TaskSpec {
+    pub fn source_names(&self) -> Vec<&str> {
+        self.sources
+            .iter()
+            .map(|source| source.source.as_str())
+            .collect()
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct SourceMetricSpec {
+struct BuiltinSource {
     source: &'static str,
     label: &'static str,
     metric: &'static str,
     sort: SortDirection,
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct TaskSpec {
-    task: RecommendTask,
+struct BuiltinTask {
+    name: &'static str,
     description: &'static str,
-    sources: &'static [SourceMetricSpec],
+    sources: &'static [BuiltinSource],
+}
+
+impl BuiltinTask {
+    fn spec(&self) -> TaskSpec {
+        TaskSpec {
+            name: self.name.to_string(),
+            description: self.description.to_string(),
+            origin: TaskOrigin::BuiltIn,
+            sources: self
+                .sources
+                .iter()
+                .map(|source| SourceMetricSpec {
+                    source: source.source.to_string(),
+                    label: source.label.to_string(),
+                    metric: source.metric.to_string(),
+                    sort: source.sort,
+                    weight: 1.0,
+                })
+                .collect(),
+        }
+    }
 }
 
-const CODING_SOURCES: &[SourceMetricSpec] = &[
-    SourceMetricSpec {
+const CODING_SOURCES: &[BuiltinSource] = &[
+    BuiltinSource {
         source: "swebench",
         label: "SWE-bench",
         metric: "resolved_rate",
         sort: SortDirection::Desc,
     },
-    SourceMetricSpec {
+    BuiltinSource {
         source: "terminal-bench",
         label: "Terminal-Bench",
         metric: "tasks_completed",
         sort: SortDirection::Desc,
     },
-    SourceMetricSpec {
+    BuiltinSource {
         source: "aider",
         label: "Aider",
         metric: "pass_rate_1",
         sort: SortDirection::Desc,
     },
-    SourceMetricSpec {
+    BuiltinSource {
         source: "swe-rebench",
         label: "SWE-rebench",
         metric: "resolve_rate",
@@ -71,14 +124,14 @@ const CODING_SOURCES: &[SourceMetricSpec] = &[
     },
 ];
 
-const AGENTIC_SOURCES: &[SourceMetricSpec] = &[
-    SourceMetricSpec {
+const AGENTIC_SOURCES: &[BuiltinSource] = &[
+    BuiltinSource {
         source: "terminal-bench",
         label: "Terminal-Bench",
         metric: "tasks_completed",
         sort: SortDirection::Desc,
     },
-    SourceMetricSpec {
+    BuiltinSource {
         source: "seal",
         label: "SEAL",
         metric: "overall_score",
@@ -86,65 +139,97 @@ const AGENTIC_SOURCES: &[SourceMetricSpec] = &[
     },
 ];
 
-const INTELLIGENCE_SOURCES: &[SourceMetricSpec] = &[SourceMetricSpec {
+const INTELLIGENCE_SOURCES: &[BuiltinSource] = &[BuiltinSource {
     source: "artificial-analysis",
     label: "Artificial Analysis",
     metric: "intelligence_index",
     sort: SortDirection::Desc,
 }];
 
-const GENERAL_SOURCES: &[SourceMetricSpec] = &[SourceMetricSpec {
+const GENERAL_SOURCES: &[BuiltinSource] = &[BuiltinSource {
     source: "arena",
     label: "Arena",
     metric: "elo_score",
     sort: SortDirection::Desc,
 }];
 
-const COST_SOURCES: &[SourceMetricSpec] = &[SourceMetricSpec {
+const COST_SOURCES: &[BuiltinSource] = &[BuiltinSource {
     source: "openrouter",
     label: "OpenRouter",
     metric: "total_cost",
     sort: SortDirection::Asc,
 }];
 
-const TASK_SPECS: &[TaskSpec] = &[
-    TaskSpec {
-        task: RecommendTask::Coding,
+const BUILTIN_TASKS: &[BuiltinTask] = &[
+    BuiltinTask {
+        name: "coding",
         description: "Use coding benchmarks with SWE-bench as the primary signal.",
         sources: CODING_SOURCES,
     },
-    TaskSpec {
-        task: RecommendTask::Agentic,
+    BuiltinTask {
+        name: "agentic",
         description: "Use agentic execution benchmarks with Terminal-Bench weighted first.",
         sources: AGENTIC_SOURCES,
     },
-    TaskSpec {
-        task: RecommendTask::Intelligence,
+    BuiltinTask {
+        name: "intelligence",
         description: "Use Artificial Analysis intelligence index; max-effort variants are best.",
         sources: INTELLIGENCE_SOURCES,
     },
-    TaskSpec {
-        task: RecommendTask::General,
+    BuiltinTask {
+        name: "general",
         description: "Use Arena human preference ELO for general-purpose model choice.",
         sources: GENERAL_SOURCES,
     },
-    TaskSpec {
-        task: RecommendTask::Cost,
-        description: "Use OpenRouter pricing and rank the cheapest models first.",
+    BuiltinTask {
+        name: "cost",
+        description: "Price the [cost] token mix at OpenRouter rates; cheapest first.",
         sources: COST_SOURCES,
     },
 ];
 
 #[derive(Debug, Clone, Serialize)]
-struct RankedModel {
-    rank: usize,
-    model: String,
-    metrics: BTreeMap<String, Option<RecommendMetricValue>>,
+pub struct RankedModel {
+    pub rank: usize,
+    pub model: String,
+    /// Weighted mean of `components`, with unlisted sources at 0.5
+    pub composite: Option<f64>,
+    /// The model's rank percentile in each source, 1.0 = best
+    pub components: BTreeMap<String, Option<f64>>,
+    pub metrics: BTreeMap<String, Option<RecommendMetricValue>>,
+    /// Where each source's value comes from and how it places the model
+    pub cells: BTreeMap<String, Option<SourceCell>>,
+    /// One sentence per source, plus the overall position, ready to quote
+    pub reasons: Vec<String>,
+    /// Whether the cells were read from rows of different effort
+    pub mixed_effort: bool,
+}
+
+/// One source's reading of a ranked model.
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceCell {
+    pub value: RecommendMetricValue,
+    /// Source row the value was read from
+    pub source_model_name: String,
+    /// How the alias map matched that row: exact, alias, prefix or no-match
+    pub match_kind: &'static str,
+    /// Effort of that row; differing efforts across a row's cells are flagged
+    pub effort: Effort,
+    /// Competition rank by the task metric among the source's models, 1 = best
+    pub rank: usize,
+    pub total: usize,
+    /// Mid-rank percentile, 1.0 = best
+    pub percentile: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetched_at: Option<chrono::DateTime<Utc>>,
+    /// Whole days since `fetched_at`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age_days: Option<i64>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize)]
 #[serde(rename_all = "snake_case")]
-enum RecommendMetricValue {
+pub enum RecommendMetricValue {
     Float(f64),
     Int(i64),
 }
@@ -160,52 +245,149 @@ struct RecommendSourceStatus {
 #[derive(Debug, Serialize)]
 struct RecommendOutput {
     timestamp: chrono::DateTime<Utc>,
-    task: RecommendTask,
-    description: &'static str,
+    task: String,
+    description: String,
     effort: String,
+    scoring: Scoring,
     top: usize,
     sources: Vec<RecommendSourceStatus>,
+    constraints: Constraints,
     rows: Vec<RankedModel>,
+    /// Models that would have ranked at or above the last row but break a
+    /// constraint
+    eliminated: Vec<Eliminated>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Eliminated {
+    /// Position with no constraints applied
+    pub rank: usize,
+    pub model: String,
+    pub reasons: Vec<String>,
 }
 
 #[derive(Debug, Clone, Default)]
 struct AggregatedModel {
     model: String,
     metrics: BTreeMap<String, RecommendMetricValue>,
+    /// Rank percentile within each source that lists the model
+    components: BTreeMap<String, f64>,
+    composite: Option<f64>,
+    cells: BTreeMap<String, SourceCell>,
 }
 
-pub fn spec_for_task(task: RecommendTask) -> &'static TaskSpec {
-    TASK_SPECS
+/// Every task `recommend` knows: the built-ins, each replaced by a config
+/// task of the same name, then the remaining config tasks in name order.
+pub fn task_specs(config: &RecommendConfig) -> Result<Vec<TaskSpec>> {
+    let mut specs: Vec<TaskSpec> = BUILTIN_TASKS.iter().map(BuiltinTask::spec).collect();
+
+    let mut custom: Vec<(&String, &TaskConfig)> = config.tasks.iter().collect();
+    custom.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, task) in custom {
+        let spec = custom_spec(name, task)?;
+        match specs.iter_mut().find(|existing| existing.name == spec.name) {
+            Some(existing) => *existing = spec,
+            None => specs.push(spec),
+        }
+    }
+    Ok(specs)
+}
+
+pub fn spec_for_task(config: &RecommendConfig, name: &str) -> Result<TaskSpec> {
+    let specs = task_specs(config)?;
+    let available = specs
         .iter()
-        .find(|spec| spec.task == task)
-        .expect("missing recommend task spec")
+        .map(|spec| spec.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    specs
+        .into_iter()
+        .find(|spec| spec.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| anyhow!("Unknown recommend task '{name}'. Use one of: {available}"))
 }
 
-pub fn list_tasks(format: OutputFormat) -> Result<String> {
-    match format {
-        OutputFormat::Json => {
-            let tasks = TASK_SPECS
-                .iter()
-                .map(|spec| TaskSpecJson {
-                    task: spec.task,
-                    description: spec.description,
-                    sources: spec.sources.iter().map(|source| source.source).collect(),
-                })
-                .collect::<Vec<_>>();
-            Ok(serde_json::to_string_pretty(&tasks)?)
+fn custom_spec(name: &str, task: &TaskConfig) -> Result<TaskSpec> {
+    if task.sources.is_empty() {
+        bail!("recommend task '{name}' lists no sources");
+    }
+    let known: Vec<String> = sources::all_sources()
+        .iter()
+        .map(|source| source.name().to_string())
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut specs = Vec::with_capacity(task.sources.len());
+    for source in &task.sources {
+        if !known.contains(&source.source) {
+            bail!(
+                "recommend task '{name}': unknown source '{}'. Available sources: {}",
+                source.source,
+                known.join(", ")
+            );
         }
-        OutputFormat::Table | OutputFormat::Markdown => {
-            let mut lines = Vec::with_capacity(TASK_SPECS.len() + 1);
-            lines.push("Task          Description".to_string());
-            lines.push(
-                "------------  --------------------------------------------------------------"
-                    .to_string(),
+        if !seen.insert(source.source.as_str()) {
+            bail!(
+                "recommend task '{name}' lists '{}' more than once",
+                source.source
+            );
+        }
+        if !(source.weight.is_finite() && source.weight >= 0.0) {
+            bail!(
+                "recommend task '{name}': weight for '{}' must be a non-negative number",
+                source.source
             );
-            for spec in TASK_SPECS {
+        }
+        specs.push(SourceMetricSpec {
+            source: source.source.clone(),
+            label: source
+                .label
+                .clone()
+                .unwrap_or_else(|| default_label(&source.source)),
+            metric: source.metric.clone(),
+            sort: source.sort,
+            weight: source.weight,
+        });
+    }
+
+    Ok(TaskSpec {
+        name: name.to_lowercase(),
+        description: task.description.clone(),
+        origin: TaskOrigin::Config,
+        sources: specs,
+    })
+}
+
+/// The label the built-in tasks use for `source`, or the source name itself.
+fn default_label(source: &str) -> String {
+    BUILTIN_TASKS
+        .iter()
+        .flat_map(|task| task.sources)
+        .find(|builtin| builtin.source == source)
+        .map_or_else(|| source.to_string(), |builtin| builtin.label.to_string())
+}
+
+pub fn list_tasks(config: &RecommendConfig, format: OutputFormat) -> Result<String> {
+    let specs = task_specs(config)?;
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(&specs)?),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let width = specs
+                .iter()
+                .map(|spec| spec.name.len())
+                .chain([12])
+                .max()
+                .unwrap_or(12);
+            let mut lines = Vec::with_capacity(specs.len() + 2);
+            lines.push(format!("{:<width$}  Description", "Task"));
+            lines.push(format!("{}  {}", "-".repeat(width), "-".repeat(62)));
+            for spec in &specs {
+                let origin = match spec.origin {
+                    TaskOrigin::BuiltIn => "",
+                    TaskOrigin::Config => " (config)",
+                };
                 lines.push(format!(
-                    "{:<12}  {}",
-                    task_name(spec.task),
-                    spec.description
+                    "{:<width$}  {}{origin}",
+                    spec.name, spec.description
                 ));
             }
             Ok(lines.join("\n"))
@@ -213,43 +395,227 @@ pub fn list_tasks(format: OutputFormat) -> Result<String> {
     }
 }
 
-#[derive(Serialize)]
-struct TaskSpecJson {
-    task: RecommendTask,
-    description: &'static str,
-    sources: Vec<&'static str>,
+/// Knobs shared by every recommend task.
+#[derive(Debug, Clone)]
+pub struct RecommendOptions {
+    pub top: usize,
+    pub effort: EffortFilter,
+    pub latest_per_family: bool,
+    /// Token mix the `cost` task prices
+    pub cost_mix: TokenMix,
+    pub scoring: Scoring,
+    /// Which row represents a model in every one of the task's sources
+    pub variant: VariantPolicy,
+    pub constraints: Constraints,
+}
+
+/// How `recommend` orders a task's models.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scoring {
+    /// Weighted mean of the model's rank percentile in each source that lists it
+    #[default]
+    Composite,
+    /// The first source's metric; the other sources only break ties
+    Primary,
+}
+
+/// Hard limits on which models `recommend` may return. A model that breaks
+/// one is dropped before `--top` applies, so the next model takes its place.
+#[derive(Debug, Clone, Default, Args, Serialize)]
+pub struct Constraints {
+    /// Highest blended OpenRouter price per 1M tokens (3 input : 1 output)
+    #[arg(long, value_name = "USD")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_price_per_1m: Option<f64>,
+    /// Smallest OpenRouter context window, in tokens (e.g. 128k, 1M)
+    #[arg(long, value_name = "TOKENS", value_parser = parse_token_count)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_context: Option<u64>,
+    /// Only models whose OpenRouter id sits under one of these providers, such
+    /// as `anthropic/…` (comma-separated)
+    #[arg(
+        long = "provider",
+        alias = "vendor",
+        value_name = "PROVIDER",
+        value_delimiter = ','
+    )]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub providers: Vec<String>,
+    /// Models or families to leave out (comma-separated)
+    #[arg(long, value_name = "MODEL", value_delimiter = ',')]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// Ignore sources whose data is older than N days (or of unknown age)
+    #[arg(long, value_name = "DAYS")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u64>,
+    /// Fewest of the task's sources that must list a model
+    #[arg(long, default_value_t = 1)]
+    pub min_sources: usize,
+}
+
+impl Constraints {
+    fn needs_openrouter(&self) -> bool {
+        self.max_price_per_1m.is_some() || self.min_context.is_some() || !self.providers.is_empty()
+    }
+
+    /// Every constraint `model` breaks, each naming its flag.
+    fn violations(
+        &self,
+        model: &AggregatedModel,
+        data: &ConstraintData,
+        aliases: &AliasMap,
+    ) -> Vec<String> {
+        let canonical = model.model.as_str();
+        let mut reasons = Vec::new();
+
+        if let Some(name) = self.exclude.iter().find(|name| {
+            let name = name.to_lowercase();
+            aliases.resolve(&name) == canonical
+                || aliases
+                    .expand_family(&name)
+                    .iter()
+                    .any(|member| member == canonical)
+        }) {
+            reasons.push(format!("--exclude {name}"));
+        }
+
+        if model.metrics.len() < self.min_sources {
+            reasons.push(format!(
+                "--min-sources {}: listed by {} of the task's sources",
+                self.min_sources,
+                model.metrics.len()
+            ));
+        }
+
+        let Some(listings) = &data.listings else {
+            return reasons;
+        };
+        let listing = listings.get(canonical);
+
+        if let Some(max) = self.max_price_per_1m {
+            match listing.and_then(|listing| listing.price) {
+                Some(price) if price <= max => {}
+                Some(price) => reasons.push(format!(
+                    "--max-price-per-1m {max}: ${price:.2} blended on OpenRouter"
+                )),
+                None => reasons.push(format!("--max-price-per-1m {max}: no OpenRouter price")),
+            }
+        }
+
+        if let Some(min) = self.min_context {
+            match listing.and_then(|listing| listing.context) {
+                Some(context) if context >= min => {}
+                Some(context) => reasons.push(format!(
+                    "--min-context {}: {} context window",
+                    format_tokens(min),
+                    format_tokens(context)
+                )),
+                None => reasons.push(format!(
+                    "--min-context {}: no OpenRouter context length",
+                    format_tokens(min)
+                )),
+            }
+        }
+
+        if !self.providers.is_empty() {
+            let served: Vec<&str> = listing
+                .map(|listing| listing.providers.iter().map(String::as_str).collect())
+                .unwrap_or_default();
+            let allowed = served.iter().any(|provider| {
+                self.providers
+                    .iter()
+                    .any(|wanted| wanted.eq_ignore_ascii_case(provider))
+            });
+            if !allowed {
+                let wanted = self.providers.join(",");
+                reasons.push(if served.is_empty() {
+                    format!("--provider {wanted}: not listed on OpenRouter")
+                } else {
+                    format!("--provider {wanted}: listed under {}", served.join(", "))
+                });
+            }
+        }
+
+        reasons
+    }
+}
+
+/// What OpenRouter lists for one canonical model, across all its entries.
+#[derive(Debug, Clone, Default)]
+pub struct Listing {
+    /// Cheapest blended price per 1M tokens
+    pub price: Option<f64>,
+    /// Largest context window
+    pub context: Option<u64>,
+    /// Provider prefixes of the OpenRouter ids, lowercased
+    pub providers: BTreeSet<String>,
+}
+
+/// What the constraints are checked against, gathered once per run.
+#[derive(Debug, Default)]
+struct ConstraintData {
+    /// Sources dropped by `--max-age-days`
+    stale: Vec<String>,
+    /// OpenRouter listings by canonical model, when a constraint needs them
+    listings: Option<HashMap<String, Listing>>,
+}
+
+pub fn openrouter_listings(result: &SourceResult, aliases: &AliasMap) -> HashMap<String, Listing> {
+    let mut listings: HashMap<String, Listing> = HashMap::new();
+    for score in &result.scores {
+        let listing = listings.entry(aliases.canonical_for(score)).or_default();
+        if let Some(price) = metric_value(score, "blended") {
+            listing.price = Some(listing.price.map_or(price, |cheapest| cheapest.min(price)));
+        }
+        if let Some(MetricValue::Int(context)) = score.metrics.get("context_length") {
+            let context = (*context).max(0) as u64;
+            listing.context = Some(
+                listing
+                    .context
+                    .map_or(context, |widest| widest.max(context)),
+            );
+        }
+        if let Some((provider, _)) = score.source_model_name.split_once('/') {
+            listing.providers.insert(provider.to_lowercase());
+        }
+    }
+    listings
+}
+
+/// Sources with data older than `max_age_days`, or of unknown age, matching
+/// `rank --max-age`.
+fn stale_sources(results: &[SourceResult], max_age_days: Option<u64>) -> Vec<String> {
+    let Some(days) = max_age_days else {
+        return Vec::new();
+    };
+    let cutoff = Utc::now() - chrono::Duration::days(days as i64);
+    results
+        .iter()
+        .filter(|result| !result.scores.is_empty())
+        .filter(|result| result.fetched_at.is_none_or(|at| at < cutoff))
+        .map(|result| result.source.clone())
+        .collect()
 }
 
 pub fn run(
     config: &Config,
     cache: &Cache,
     aliases: &AliasMap,
-    task: RecommendTask,
-    top: usize,
-    effort: AaEffortFilter,
+    task: &str,
+    options: &RecommendOptions,
     format: OutputFormat,
 ) -> Result<()> {
-    let spec = spec_for_task(task);
-    let mut results = fetch_recommend_sources(config, cache, spec.sources)?;
-
-    if spec
-        .sources
-        .iter()
-        .any(|source_spec| source_spec.source == "artificial-analysis")
-    {
-        apply_aa_effort_filter(&mut results, effort);
-    }
-
-    for result in &results {
-        eprintln!("[{}] {}", result.source, status_label(&result.status));
-    }
-
-    let rows = rank_models(spec, &results, aliases, top);
+    let (top, effort) = (options.top, options.effort);
+    let spec = &spec_for_task(&config.recommend, task)?;
+    let (results, selection) = select_models(config, cache, aliases, spec, options)?;
     let output = RecommendOutput {
         timestamp: Utc::now(),
-        task,
-        description: spec.description,
+        task: spec.name.clone(),
+        description: spec.description.clone(),
         effort: format!("{effort:?}").to_lowercase(),
+        scoring: options.scoring,
         top,
         sources: results
             .iter()
@@ -265,7 +631,9 @@ pub fn run(
                 fetched_at: result.fetched_at,
             })
             .collect(),
-        rows,
+        constraints: options.constraints.clone(),
+        rows: selection.rows,
+        eliminated: selection.eliminated,
     };
 
     let rendered = match format {
@@ -278,34 +646,263 @@ pub fn run(
     Ok(())
 }
 
-fn fetch_recommend_sources(
+/// One task's column in the `--all-tasks` matrix.
+#[derive(Debug, Serialize)]
+struct TaskRanking {
+    task: String,
+    description: String,
+    rows: Vec<RankedModel>,
+    eliminated: Vec<Eliminated>,
+}
+
+/// Where a model placed in one task.
+#[derive(Debug, Serialize)]
+struct Placement {
+    task: String,
+    rank: usize,
+}
+
+/// A model in the top `top` of more than one task.
+#[derive(Debug, Serialize)]
+struct Highlight {
+    model: String,
+    placements: Vec<Placement>,
+}
+
+#[derive(Debug, Serialize)]
+struct AllTasksOutput {
+    timestamp: chrono::DateTime<Utc>,
+    effort: String,
+    scoring: Scoring,
+    top: usize,
+    sources: Vec<RecommendSourceStatus>,
+    constraints: Constraints,
+    tasks: Vec<TaskRanking>,
+    /// Models that make the top of several tasks, most tasks first
+    highlights: Vec<Highlight>,
+}
+
+/// Rank every task from one fetch of the sources they need between them.
+pub fn run_all(
+    config: &Config,
+    cache: &Cache,
+    aliases: &AliasMap,
+    options: &RecommendOptions,
+    format: OutputFormat,
+) -> Result<()> {
+    let specs = task_specs(&config.recommend)?;
+    let mut names: Vec<&str> = Vec::new();
+    for spec in &specs {
+        for name in spec.source_names() {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    if options.constraints.needs_openrouter() && !names.contains(&"openrouter") {
+        names.push("openrouter");
+    }
+
+    let results = prepare_sources(config, cache, aliases, &names, options)?;
+    let data = constraint_data(config, cache, aliases, &results, options)?;
+    let tasks: Vec<TaskRanking> = specs
+        .iter()
+        .map(|spec| {
+            warn_missing_metrics(spec, &results, &options.cost_mix);
+            let selection = rank_models(spec, &results, aliases, options, &data);
+            TaskRanking {
+                task: spec.name.clone(),
+                description: spec.description.clone(),
+                rows: selection.rows,
+                eliminated: selection.eliminated,
+            }
+        })
+        .collect();
+
+    let output = AllTasksOutput {
+        timestamp: Utc::now(),
+        effort: format!("{:?}", options.effort).to_lowercase(),
+        scoring: options.scoring,
+        top: options.top,
+        sources: results
+            .iter()
+            .map(|result| RecommendSourceStatus {
+                label: default_label(&result.source),
+                source: result.source.clone(),
+                status: status_label(&result.status).to_string(),
+                fetched_at: result.fetched_at,
+            })
+            .collect(),
+        constraints: options.constraints.clone(),
+        highlights: highlights(&tasks),
+        tasks,
+    };
+
+    let rendered = match format {
+        OutputFormat::Json => serde_json::to_string_pretty(&output)?,
+        OutputFormat::Table => render_matrix(&output, false),
+        OutputFormat::Markdown => render_matrix(&output, true),
+    };
+    println!("{rendered}");
+    Ok(())
+}
+
+/// Models listed by more than one task, by task count, then by mean rank,
+/// then by name.
+fn highlights(tasks: &[TaskRanking]) -> Vec<Highlight> {
+    let mut placements: BTreeMap<&str, Vec<Placement>> = BTreeMap::new();
+    for task in tasks {
+        for row in &task.rows {
+            placements.entry(&row.model).or_default().push(Placement {
+                task: task.task.clone(),
+                rank: row.rank,
+            });
+        }
+    }
+
+    let mean_rank = |highlight: &Highlight| {
+        let total: usize = highlight.placements.iter().map(|p| p.rank).sum();
+        total as f64 / highlight.placements.len() as f64
+    };
+    let mut highlights: Vec<Highlight> = placements
+        .into_iter()
+        .filter(|(_, placements)| placements.len() > 1)
+        .map(|(model, placements)| Highlight {
+            model: model.to_string(),
+            placements,
+        })
+        .collect();
+    highlights.sort_by(|left, right| {
+        right
+            .placements
+            .len()
+            .cmp(&left.placements.len())
+            .then(mean_rank(left).total_cmp(&mean_rank(right)))
+    });
+    highlights
+}
+
+/// Fetch a task's sources, apply the effort, family and constraint filters
+/// and rank what is left.
+pub fn select_models(
+    config: &Config,
+    cache: &Cache,
+    aliases: &AliasMap,
+    spec: &TaskSpec,
+    options: &RecommendOptions,
+) -> Result<(Vec<SourceResult>, Selection)> {
+    let results = prepare_sources(config, cache, aliases, &spec.source_names(), options)?;
+    warn_missing_metrics(spec, &results, &options.cost_mix);
+    let data = constraint_data(config, cache, aliases, &results, options)?;
+    let selection = rank_models(spec, &results, aliases, options, &data);
+    Ok((results, selection))
+}
+
+/// Warn about each of `spec`'s sources that returned rows but none carrying
+/// the metric the task reads, which is usually a typo in a config task.
+pub fn warn_missing_metrics(spec: &TaskSpec, results: &[SourceResult], cost_mix: &TokenMix) {
+    for source_spec in missing_metrics(spec, results, cost_mix) {
+        eprintln!(
+            "[warn] recommend task '{}': no {} row has metric '{}'",
+            spec.name, source_spec.source, source_spec.metric
+        );
+    }
+}
+
+/// Task sources whose fetched rows all lack the task's metric for them.
+/// Sources with no rows at all are left to their fetch status.
+pub fn missing_metrics<'a>(
+    spec: &'a TaskSpec,
+    results: &[SourceResult],
+    cost_mix: &TokenMix,
+) -> Vec<&'a SourceMetricSpec> {
+    spec.sources
+        .iter()
+        .filter(|source_spec| {
+            results
+                .iter()
+                .find(|result| result.source == source_spec.source)
+                .is_some_and(|result| {
+                    !result.scores.is_empty()
+                        && result.scores.iter().all(|score| {
+                            extract_metric(score, &source_spec.metric, cost_mix).is_none()
+                        })
+                })
+        })
+        .collect()
+}
+
+/// Fetch `names` and apply the effort and family filters, reporting each
+/// source's status.
+fn prepare_sources(
     config: &Config,
     cache: &Cache,
-    specs: &[SourceMetricSpec],
+    aliases: &AliasMap,
+    names: &[&str],
+    options: &RecommendOptions,
+) -> Result<Vec<SourceResult>> {
+    let mut results = fetch_recommend_sources(config, cache, names)?;
+
+    apply_effort_filter(&mut results, options.effort, aliases);
+
+    if options.latest_per_family {
+        crate::retain_latest_per_family(&mut results, aliases);
+    }
+
+    for result in &results {
+        eprintln!("[{}] {}", result.source, status_label(&result.status));
+    }
+    Ok(results)
+}
+
+/// Stale sources and, when a constraint needs them, OpenRouter listings,
+/// fetching OpenRouter if `results` doesn't already hold it.
+fn constraint_data(
+    config: &Config,
+    cache: &Cache,
+    aliases: &AliasMap,
+    results: &[SourceResult],
+    options: &RecommendOptions,
+) -> Result<ConstraintData> {
+    let constraints = &options.constraints;
+    let stale = stale_sources(results, constraints.max_age_days);
+    for source in &stale {
+        eprintln!(
+            "[{source}] ignored: older than --max-age-days {}",
+            constraints.max_age_days.unwrap_or_default()
+        );
+    }
+    let listings = if !constraints.needs_openrouter() {
+        None
+    } else if let Some(result) = results.iter().find(|result| result.source == "openrouter") {
+        Some(openrouter_listings(result, aliases))
+    } else {
+        let fetched = fetch_recommend_sources(config, cache, &["openrouter"])?;
+        Some(openrouter_listings(&fetched[0], aliases))
+    };
+    Ok(ConstraintData { stale, listings })
+}
+
+pub fn fetch_recommend_sources(
+    config: &Config,
+    cache: &Cache,
+    names: &[&str],
 ) -> Result<Vec<SourceResult>> {
-    let wanted: HashSet<&str> = specs.iter().map(|spec| spec.source).collect();
     let mut source_map: HashMap<String, Box<dyn Source>> = sources::all_sources()
         .into_iter()
         .map(|source| (source.name().to_string(), source))
         .collect();
 
-    let mut results = Vec::with_capacity(specs.len());
-    for spec in specs {
-        let Some(source) = source_map.remove(spec.source) else {
-            return Err(anyhow!(
-                "Unknown source in recommend task taxonomy: {}",
-                spec.source
-            ));
+    let mut results = Vec::with_capacity(names.len());
+    for name in names {
+        let Some(source) = source_map.remove(*name) else {
+            return Err(anyhow!("Unknown source in recommend task taxonomy: {name}"));
         };
 
-        if !wanted.contains(spec.source) {
-            continue;
-        }
-
         let result = match source.fetch(config, cache) {
             Ok(result) => result,
             Err(err) => SourceResult {
-                source: spec.source.to_string(),
+                source: name.to_string(),
                 fetched_at: None,
                 status: SourceStatus::Error(err.to_string()),
                 scores: vec![],
@@ -317,31 +914,98 @@ fn fetch_recommend_sources(
     Ok(results)
 }
 
-fn apply_aa_effort_filter(results: &mut [SourceResult], effort: AaEffortFilter) {
-    if effort == AaEffortFilter::All {
-        return;
+#[derive(Debug, Default)]
+pub struct Selection {
+    pub rows: Vec<RankedModel>,
+    pub eliminated: Vec<Eliminated>,
+}
+
+/// The top models that meet every constraint, plus the models ranked above
+/// the last of them that a constraint removed.
+fn rank_models(
+    spec: &TaskSpec,
+    results: &[SourceResult],
+    aliases: &AliasMap,
+    options: &RecommendOptions,
+    data: &ConstraintData,
+) -> Selection {
+    let unconstrained = ordered_models(spec, results, aliases, options);
+    let ordered = if data.stale.is_empty() {
+        unconstrained.clone()
+    } else {
+        let fresh: Vec<SourceResult> = results
+            .iter()
+            .filter(|result| !data.stale.contains(&result.source))
+            .cloned()
+            .collect();
+        ordered_models(spec, &fresh, aliases, options)
+    };
+    let fresh_models: HashSet<String> = ordered.iter().map(|model| model.model.clone()).collect();
+
+    let mut rows: Vec<RankedModel> = Vec::new();
+    let mut broken: HashMap<String, Vec<String>> = HashMap::new();
+    for model in ordered {
+        let reasons = options.constraints.violations(&model, data, aliases);
+        if !reasons.is_empty() {
+            broken.insert(model.model.clone(), reasons);
+        } else if rows.len() < options.top {
+            rows.push(ranked_row(spec, rows.len() + 1, model));
+        }
     }
 
-    for result in results {
-        if result.source != "artificial-analysis" {
-            continue;
+    // Only models that would otherwise have made the list are worth reporting
+    let cutoff = if rows.len() < options.top {
+        unconstrained.len()
+    } else {
+        rows.last()
+            .and_then(|last| {
+                unconstrained
+                    .iter()
+                    .position(|model| model.model == last.model)
+            })
+            .map_or(0, |at| at + 1)
+    };
+    let stale_reason = |model: &str| {
+        let days = options.constraints.max_age_days.unwrap_or_default();
+        let stale = data.stale.join(", ");
+        if fresh_models.contains(model) {
+            format!("--max-age-days {days}: ranks lower without {stale}")
+        } else {
+            format!("--max-age-days {days}: listed only by {stale}")
         }
+    };
+    let eliminated = unconstrained[..cutoff]
+        .iter()
+        .enumerate()
+        .filter(|(_, model)| !rows.iter().any(|row| row.model == model.model))
+        .filter_map(|(at, model)| {
+            let reasons = match broken.remove(&model.model) {
+                Some(reasons) => reasons,
+                None if !data.stale.is_empty() => vec![stale_reason(&model.model)],
+                None => return None,
+            };
+            Some(Eliminated {
+                rank: at + 1,
+                model: model.model.clone(),
+                reasons,
+            })
+        })
+        .collect();
 
-        result
-            .scores
-            .retain(|score| effort.matches(classify_effort_level(&score.source_model_name)));
-    }
+    Selection { rows, eliminated }
 }
 
-fn rank_models(
+/// Every model with at least one of the task's metrics, best first.
+fn ordered_models(
     spec: &TaskSpec,
     results: &[SourceResult],
     aliases: &AliasMap,
-    top: usize,
-) -> Vec<RankedModel> {
+    options: &RecommendOptions,
+) -> Vec<AggregatedModel> {
     let mut models: HashMap<String, AggregatedModel> = HashMap::new();
+    let mut reporting: HashSet<&str> = HashSet::new();
 
-    for source_spec in spec.sources {
+    for source_spec in &spec.sources {
         let Some(result) = results
             .iter()
             .find(|result| result.source == source_spec.source)
@@ -349,88 +1013,514 @@ fn rank_models(
             continue;
         };
 
-        let mut best_for_source: HashMap<String, RecommendMetricValue> = HashMap::new();
-        for score in &result.scores {
-            let Some(metric) = extract_metric(score, source_spec.metric) else {
-                continue;
-            };
-            let model = canonical_model_name(aliases, score);
-            match best_for_source.get(&model) {
-                Some(existing) if !is_better(metric, *existing, source_spec.sort) => {}
-                _ => {
-                    best_for_source.insert(model, metric);
-                }
-            }
+        let rows = best_rows(
+            source_spec,
+            result,
+            aliases,
+            &options.cost_mix,
+            options.variant,
+        );
+        if !rows.is_empty() {
+            reporting.insert(&source_spec.source);
         }
-
-        for (model, metric) in best_for_source {
+        let values: HashMap<String, RecommendMetricValue> = rows
+            .iter()
+            .map(|(model, row)| (model.clone(), row.value))
+            .collect();
+        let standings = source_standings(&values, source_spec.sort);
+        let age_days = result
+            .fetched_at
+            .map(|fetched_at| (Utc::now() - fetched_at).num_days());
+        for (model, row) in rows {
+            let (rank, percentile) = standings[&model];
             let entry = models
                 .entry(model.clone())
                 .or_insert_with(|| AggregatedModel {
                     model,
-                    metrics: BTreeMap::new(),
+                    ..AggregatedModel::default()
                 });
-            entry.metrics.insert(source_spec.source.to_string(), metric);
+            entry.metrics.insert(source_spec.source.clone(), row.value);
+            entry
+                .components
+                .insert(source_spec.source.clone(), percentile);
+            entry.cells.insert(
+                source_spec.source.clone(),
+                SourceCell {
+                    value: row.value,
+                    source_model_name: row.source_model_name,
+                    match_kind: row.match_kind,
+                    effort: row.effort,
+                    rank,
+                    total: values.len(),
+                    percentile,
+                    fetched_at: result.fetched_at,
+                    age_days,
+                },
+            );
         }
     }
 
-    let primary = spec
-        .sources
-        .first()
-        .expect("recommend spec missing primary source");
     let mut ranked: Vec<AggregatedModel> = models
         .into_values()
         .filter(|model| !model.metrics.is_empty())
+        .map(|mut model| {
+            model.composite = composite_score(spec, &reporting, &model.components);
+            model
+        })
         .collect();
 
-    ranked.sort_by(|left, right| compare_models(spec, primary, left, right));
-    ranked.truncate(top);
-
+    ranked.sort_by(|left, right| compare_models(spec, options.scoring, left, right));
     ranked
+}
+
+/// The row a task reads for one model in one source.
+#[derive(Debug, Clone)]
+pub struct TaskRow {
+    pub source_model_name: String,
+    /// How the alias map matched the row: exact, alias, prefix or no-match
+    pub match_kind: &'static str,
+    pub effort: Effort,
+    pub value: RecommendMetricValue,
+}
+
+/// The row `policy` picks per canonical model in `result`, out of the rows
+/// carrying the source's task metric ordered best value first.
+pub fn best_rows(
+    source_spec: &SourceMetricSpec,
+    result: &SourceResult,
+    aliases: &AliasMap,
+    cost_mix: &TokenMix,
+    policy: VariantPolicy,
+) -> HashMap<String, TaskRow> {
+    let mut candidates: HashMap<String, Vec<(&ModelScore, RecommendMetricValue)>> = HashMap::new();
+    for score in &result.scores {
+        let Some(value) = extract_metric(score, &source_spec.metric, cost_mix) else {
+            continue;
+        };
+        candidates
+            .entry(aliases.canonical_for(score))
+            .or_default()
+            .push((score, value));
+    }
+
+    candidates
         .into_iter()
-        .enumerate()
-        .map(|(index, model)| RankedModel {
-            rank: index + 1,
-            model: model.model,
-            metrics: spec
-                .sources
-                .iter()
-                .map(|source_spec| {
-                    (
-                        source_spec.source.to_string(),
-                        model.metrics.get(source_spec.source).copied(),
-                    )
-                })
-                .collect(),
+        .map(|(model, mut rows)| {
+            rows.sort_by(|left, right| compare_metric(left.1, right.1, source_spec.sort));
+            let scores: Vec<&ModelScore> = rows.iter().map(|(score, _)| *score).collect();
+            let picked = policy.pick(&scores);
+            let (score, value) = rows
+                .into_iter()
+                .find(|(score, _)| std::ptr::eq(*score, picked))
+                .expect("the policy picks one of the candidates");
+            let row = TaskRow {
+                source_model_name: score.source_model_name.clone(),
+                match_kind: aliases.match_kind_for(score).as_str(),
+                effort: score.variant.effort,
+                value,
+            };
+            (model, row)
         })
         .collect()
 }
 
-fn compare_models(
+/// Each model's competition rank within one source, and its rank percentile:
+/// 1.0 for the best value, 0.0 for the worst, with ties sharing their mid-rank.
+fn source_standings(
+    values: &HashMap<String, RecommendMetricValue>,
+    sort: SortDirection,
+) -> HashMap<String, (usize, f64)> {
+    values
+        .iter()
+        .map(|(model, value)| {
+            let (mut better, mut equal) = (0, 0);
+            for other in values.values() {
+                match compare_metric(*other, *value, sort) {
+                    Ordering::Less => better += 1,
+                    Ordering::Equal => equal += 1,
+                    Ordering::Greater => {}
+                }
+            }
+            let mid_rank = better as f64 + (equal as f64 + 1.0) / 2.0;
+            (
+                model.clone(),
+                (better + 1, percentile(mid_rank, values.len())),
+            )
+        })
+        .collect()
+}
+
+/// Weighted mean of `components` over the task's reporting sources. A source
+/// that reports but doesn't list the model counts at the median (0.5), so a
+/// model can't top the task on one or two sources alone. `None` when every
+/// reporting source has weight 0.
+fn composite_score(
+    spec: &TaskSpec,
+    reporting: &HashSet<&str>,
+    components: &BTreeMap<String, f64>,
+) -> Option<f64> {
+    let (mut sum, mut weight) = (0.0, 0.0);
+    for source_spec in &spec.sources {
+        if !reporting.contains(source_spec.source.as_str()) {
+            continue;
+        }
+        let component = components.get(&source_spec.source).copied().unwrap_or(0.5);
+        sum += source_spec.weight * component;
+        weight += source_spec.weight;
+    }
+    (weight > 0.0).then(|| sum / weight)
+}
+
+fn ranked_row(spec: &TaskSpec, rank: usize, model: AggregatedModel) -> RankedModel {
+    let efforts: HashSet<Effort> = model.cells.values().map(|cell| cell.effort).collect();
+    RankedModel {
+        rank,
+        mixed_effort: efforts.len() > 1,
+        reasons: reasons(spec, rank, &model),
+        cells: spec
+            .sources
+            .iter()
+            .map(|source_spec| {
+                (
+                    source_spec.source.clone(),
+                    model.cells.get(&source_spec.source).cloned(),
+                )
+            })
+            .collect(),
+        composite: model.composite,
+        components: spec
+            .sources
+            .iter()
+            .map(|source_spec| {
+                (
+                    source_spec.source.clone(),
+                    model.components.get(&source_spec.source).copied(),
+                )
+            })
+            .collect(),
+        metrics: spec
+            .sources
+            .iter()
+            .map(|source_spec| {
+                (
+                    source_spec.source.clone(),
+                    model.metrics.get(&source_spec.source).copied(),
+                )
+            })
+            .collect(),
+        model: model.model,
+    }
+}
+
+/// Plain sentences behind a row: its position, then what each source says.
+fn reasons(spec: &TaskSpec, rank: usize, model: &AggregatedModel) -> Vec<String> {
+    let listed = format!(
+        "listed by {} of {} sources",
+        model.cells.len(),
+        spec.sources.len()
+    );
+    let mut reasons = vec![match model.composite {
+        Some(composite) => format!(
+            "#{rank} for {}, composite score {composite:.3}, {listed}",
+            spec.name
+        ),
+        None => format!("#{rank} for {}, {listed}", spec.name),
+    }];
+
+    for source_spec in &spec.sources {
+        let Some(cell) = model.cells.get(&source_spec.source) else {
+            reasons.push(format!("{}: not listed", source_spec.label));
+            continue;
+        };
+        let row = match cell.match_kind {
+            "exact" => String::new(),
+            "no-match" => format!(", row \"{}\" not in the alias map", cell.source_model_name),
+            kind => format!(", row \"{}\" ({kind} match)", cell.source_model_name),
+        };
+        let age = match cell.age_days {
+            Some(0) => "data from today".to_string(),
+            Some(1) => "data 1 day old".to_string(),
+            Some(days) => format!("data {days} days old"),
+            None => "data age unknown".to_string(),
+        };
+        reasons.push(format!(
+            "{}: {}, rank {} of {}{row}, {age}",
+            source_spec.label,
+            format_metric(&source_spec.metric, cell.value),
+            cell.rank,
+            cell.total
+        ));
+    }
+
+    let efforts: BTreeSet<&str> = model
+        .cells
+        .values()
+        .map(|cell| effort_name(cell.effort))
+        .collect();
+    if efforts.len() > 1 {
+        let read = spec
+            .sources
+            .iter()
+            .filter_map(|source_spec| {
+                let cell = model.cells.get(&source_spec.source)?;
+                Some(format!(
+                    "{} {}",
+                    source_spec.label,
+                    effort_name(cell.effort)
+                ))
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        reasons.push(format!("Mixes effort levels across sources: {read}"));
+    }
+    reasons
+}
+
+fn effort_name(effort: Effort) -> &'static str {
+    match effort {
+        Effort::Max => "max",
+        Effort::Standard => "standard",
+        Effort::Low => "low",
+    }
+}
+
+/// One of a task's metrics for the model being explained.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskMetric {
+    pub source: String,
+    pub label: String,
+    pub metric: String,
+    pub value: Option<RecommendMetricValue>,
+    /// Rank percentile within the source
+    pub component: Option<f64>,
+}
+
+impl TaskMetric {
+    pub fn display(&self) -> String {
+        self.value
+            .map(|value| format_metric(&self.metric, value))
+            .unwrap_or_else(|| "—".to_string())
+    }
+}
+
+/// A model ranked next to the explained one, and what ordered the pair.
+#[derive(Debug, Clone, Serialize)]
+pub struct Neighbour {
+    pub rank: usize,
+    pub model: String,
+    pub decided_by: TieBreak,
+    /// The deciding values, the explained model's first
+    pub detail: String,
+}
+
+/// Where a model lands in one recommend task, and why.
+#[derive(Debug, Serialize)]
+pub struct RecommendExplanation {
+    pub task: String,
+    /// Position among every model the task ranks, ignoring `--top`
+    pub rank: Option<usize>,
+    pub total: usize,
+    pub scoring: Scoring,
+    pub composite: Option<f64>,
+    pub metrics: Vec<TaskMetric>,
+    /// The model just ahead, which wins the comparison
+    pub above: Option<Neighbour>,
+    /// The model just behind, which loses it
+    pub below: Option<Neighbour>,
+}
+
+/// Explain `model`'s place in `task`: its metrics and how [`compare_models`]
+/// orders it against the models either side of it. `options.top` is ignored;
+/// the explanation covers every ranked model.
+pub fn explain(
+    config: &Config,
+    cache: &Cache,
+    aliases: &AliasMap,
+    task: &str,
+    options: &RecommendOptions,
+    model: &str,
+) -> Result<RecommendExplanation> {
+    let spec = spec_for_task(&config.recommend, task)?;
+    let mut results = fetch_recommend_sources(config, cache, &spec.source_names())?;
+    apply_effort_filter(&mut results, options.effort, aliases);
+    if options.latest_per_family {
+        crate::retain_latest_per_family(&mut results, aliases);
+    }
+    Ok(explain_position(&spec, &results, aliases, options, model))
+}
+
+fn explain_position(
+    spec: &TaskSpec,
+    results: &[SourceResult],
+    aliases: &AliasMap,
+    options: &RecommendOptions,
+    model: &str,
+) -> RecommendExplanation {
+    let scoring = options.scoring;
+    let ordered = ordered_models(spec, results, aliases, options);
+    let index = ordered
+        .iter()
+        .position(|candidate| candidate.model == model);
+
+    let neighbour = |at: usize, other: usize| {
+        let (_, rule) = decide(spec, scoring, &ordered[at], &ordered[other]);
+        Neighbour {
+            rank: other + 1,
+            model: ordered[other].model.clone(),
+            detail: describe_rule(spec, &rule, &ordered[at], &ordered[other]),
+            decided_by: rule,
+        }
+    };
+
+    RecommendExplanation {
+        task: spec.name.clone(),
+        rank: index.map(|at| at + 1),
+        total: ordered.len(),
+        scoring,
+        composite: index.and_then(|at| ordered[at].composite),
+        metrics: spec
+            .sources
+            .iter()
+            .map(|source_spec| TaskMetric {
+                source: source_spec.source.clone(),
+                label: source_spec.label.clone(),
+                metric: source_spec.metric.clone(),
+                value: index.and_then(|at| ordered[at].metrics.get(&source_spec.source).copied()),
+                component: index
+                    .and_then(|at| ordered[at].components.get(&source_spec.source).copied()),
+            })
+            .collect(),
+        above: index.filter(|at| *at > 0).map(|at| neighbour(at, at - 1)),
+        below: index
+            .filter(|at| at + 1 < ordered.len())
+            .map(|at| neighbour(at, at + 1)),
+    }
+}
+
+fn describe_rule(
+    spec: &TaskSpec,
+    rule: &TieBreak,
+    model: &AggregatedModel,
+    other: &AggregatedModel,
+) -> String {
+    let composite = |candidate: &AggregatedModel| {
+        candidate
+            .composite
+            .map_or_else(|| "—".to_string(), |score| format!("{score:.3}"))
+    };
+    match rule {
+        TieBreak::Composite => format!("composite {} vs {}", composite(model), composite(other)),
+        TieBreak::Primary(source) | TieBreak::Secondary(source) => {
+            let source_spec = spec
+                .sources
+                .iter()
+                .find(|source_spec| source_spec.source == *source)
+                .expect("tie-break source outside the task spec");
+            let value = |candidate: &AggregatedModel| {
+                candidate
+                    .metrics
+                    .get(source)
+                    .map(|value| format_metric(&source_spec.metric, *value))
+                    .unwrap_or_else(|| "—".to_string())
+            };
+            format!("{} {} vs {}", source_spec.label, value(model), value(other))
+        }
+        TieBreak::Coverage => format!(
+            "listed by {} vs {} of the task's sources",
+            model.metrics.len(),
+            other.metrics.len()
+        ),
+        TieBreak::Name => "every metric equal; ordered by name".to_string(),
+    }
+}
+
+fn compare_models(
+    spec: &TaskSpec,
+    scoring: Scoring,
+    left: &AggregatedModel,
+    right: &AggregatedModel,
+) -> Ordering {
+    decide(spec, scoring, left, right).0
+}
+
+/// Which step of the recommend ordering separated two models.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "rule", content = "source", rename_all = "snake_case")]
+pub enum TieBreak {
+    /// The composite scores differ, or only one model has one
+    Composite,
+    /// The task's primary metric differs, or only one model has it
+    Primary(String),
+    /// One model is listed by more of the task's sources
+    Coverage,
+    /// The first secondary source, in task order, whose metric differs
+    Secondary(String),
+    /// Every metric is equal, so the canonical names decide
+    Name,
+}
+
+impl std::fmt::Display for TieBreak {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TieBreak::Composite => write!(f, "composite score"),
+            TieBreak::Primary(source) => write!(f, "primary metric ({source})"),
+            TieBreak::Coverage => write!(f, "source coverage"),
+            TieBreak::Secondary(source) => write!(f, "secondary metric ({source})"),
+            TieBreak::Name => write!(f, "name"),
+        }
+    }
+}
+
+/// Order two models and report the rule that decided it. [`compare_models`]
+/// is this without the rule, so the two cannot disagree.
+fn decide(
     spec: &TaskSpec,
-    primary: &SourceMetricSpec,
+    scoring: Scoring,
     left: &AggregatedModel,
     right: &AggregatedModel,
-) -> Ordering {
-    let left_primary = left.metrics.get(primary.source).copied();
-    let right_primary = right.metrics.get(primary.source).copied();
-
-    compare_option_metric(left_primary, right_primary, primary.sort)
-        .then_with(|| right.metrics.len().cmp(&left.metrics.len()))
-        .then_with(|| {
-            for source_spec in spec.sources.iter().skip(1) {
-                let ordering = compare_option_metric(
-                    left.metrics.get(source_spec.source).copied(),
-                    right.metrics.get(source_spec.source).copied(),
-                    source_spec.sort,
-                );
-                if ordering != Ordering::Equal {
-                    return ordering;
-                }
-            }
-            Ordering::Equal
-        })
-        .then_with(|| left.model.cmp(&right.model))
+) -> (Ordering, TieBreak) {
+    if scoring == Scoring::Composite {
+        let ordering = match (left.composite, right.composite) {
+            (Some(left), Some(right)) => right.total_cmp(&left),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        if ordering != Ordering::Equal {
+            return (ordering, TieBreak::Composite);
+        }
+    }
+
+    let primary = spec
+        .sources
+        .first()
+        .expect("recommend spec missing primary source");
+    let ordering = compare_option_metric(
+        left.metrics.get(&primary.source).copied(),
+        right.metrics.get(&primary.source).copied(),
+        primary.sort,
+    );
+    if ordering != Ordering::Equal {
+        return (ordering, TieBreak::Primary(primary.source.clone()));
+    }
+
+    let ordering = right.metrics.len().cmp(&left.metrics.len());
+    if ordering != Ordering::Equal {
+        return (ordering, TieBreak::Coverage);
+    }
+
+    for source_spec in spec.sources.iter().skip(1) {
+        let ordering = compare_option_metric(
+            left.metrics.get(&source_spec.source).copied(),
+            right.metrics.get(&source_spec.source).copied(),
+            source_spec.sort,
+        );
+        if ordering != Ordering::Equal {
+            return (ordering, TieBreak::Secondary(source_spec.source.clone()));
+        }
+    }
+
+    (left.model.cmp(&right.model), TieBreak::Name)
 }
 
 fn compare_option_metric(
@@ -446,7 +1536,8 @@ fn compare_option_metric(
     }
 }
 
-fn compare_metric(
+/// Orders two values of one metric best first.
+pub fn compare_metric(
     left: RecommendMetricValue,
     right: RecommendMetricValue,
     direction: SortDirection,
@@ -460,19 +1551,14 @@ fn compare_metric(
     }
 }
 
-fn is_better(
-    candidate: RecommendMetricValue,
-    current: RecommendMetricValue,
-    direction: SortDirection,
-) -> bool {
-    compare_metric(candidate, current, direction) == Ordering::Less
-}
-
-fn extract_metric(score: &ModelScore, metric_name: &str) -> Option<RecommendMetricValue> {
+fn extract_metric(
+    score: &ModelScore,
+    metric_name: &str,
+    cost_mix: &TokenMix,
+) -> Option<RecommendMetricValue> {
     if metric_name == "total_cost" {
-        let prompt = metric_f64(score, "prompt_per_1m")?;
-        let completion = metric_f64(score, "completion_per_1m")?;
-        return Some(RecommendMetricValue::Float(prompt + completion));
+        let spend = cost_mix.spend(score)?;
+        return Some(RecommendMetricValue::Float(spend.total));
     }
 
     match score.metrics.get(metric_name)? {
@@ -482,28 +1568,6 @@ fn extract_metric(score: &ModelScore, metric_name: &str) -> Option<RecommendMetr
     }
 }
 
-fn metric_f64(score: &ModelScore, metric_name: &str) -> Option<f64> {
-    match score.metrics.get(metric_name)? {
-        MetricValue::Float(value) => Some(*value),
-        MetricValue::Int(value) => Some(*value as f64),
-        MetricValue::Text(_) => None,
-    }
-}
-
-fn canonical_model_name(aliases: &AliasMap, score: &ModelScore) -> String {
-    let by_model = aliases.resolve(&score.model);
-    if by_model != score.model.to_lowercase() {
-        return by_model;
-    }
-
-    let by_source_name = aliases.resolve(&score.source_model_name);
-    if by_source_name != score.source_model_name.to_lowercase() {
-        return by_source_name;
-    }
-
-    score.model.to_lowercase()
-}
-
 fn metric_as_f64(metric: RecommendMetricValue) -> f64 {
     match metric {
         RecommendMetricValue::Float(value) => value,
@@ -516,28 +1580,28 @@ fn render_table(spec: &TaskSpec, output: &RecommendOutput) -> String {
     lines.push(String::new());
     lines.push(format!(
         "Task: {}  (sources: {})",
-        task_name(output.task),
+        output.task,
         spec.sources
             .iter()
-            .map(|source| source.source)
+            .map(|source| source.source.as_str())
             .collect::<Vec<_>>()
             .join(", ")
     ));
     lines.push(String::new());
 
-    let mut headers = vec!["Rank".to_string(), "Model".to_string()];
+    let mut headers = vec!["Rank".to_string(), "Model".to_string(), "Score".to_string()];
     headers.extend(spec.sources.iter().map(|source| source.label.to_string()));
     let mut widths: Vec<usize> = headers.iter().map(|header| header.len()).collect();
 
     let mut rows: Vec<Vec<String>> = Vec::new();
     for row in &output.rows {
-        let mut values = vec![row.rank.to_string(), row.model.clone()];
-        for source_spec in spec.sources {
+        let mut values = vec![row.rank.to_string(), model_cell(row), composite_cell(row)];
+        for source_spec in &spec.sources {
             let cell = row
                 .metrics
-                .get(source_spec.source)
+                .get(&source_spec.source)
                 .and_then(|value| *value)
-                .map(|value| format_metric(source_spec.metric, value))
+                .map(|value| format_metric(&source_spec.metric, value))
                 .unwrap_or_else(|| "—".to_string());
             values.push(cell);
         }
@@ -558,6 +1622,23 @@ fn render_table(spec: &TaskSpec, output: &RecommendOutput) -> String {
     for row in rows {
         lines.push(padded_row(&row, &widths));
     }
+    if output.rows.iter().any(|row| row.mixed_effort) {
+        lines.push(String::new());
+        lines.push(MIXED_EFFORT_NOTE.to_string());
+    }
+
+    if !output.eliminated.is_empty() {
+        lines.push(String::new());
+        lines.push("Eliminated by constraints:".to_string());
+        for model in &output.eliminated {
+            lines.push(format!(
+                "  #{} {}: {}",
+                model.rank,
+                model.model,
+                model.reasons.join("; ")
+            ));
+        }
+    }
     lines.join("\n")
 }
 
@@ -565,16 +1646,16 @@ fn render_markdown(spec: &TaskSpec, output: &RecommendOutput) -> String {
     let mut lines = Vec::new();
     lines.push(format!(
         "**Task:** `{}`  \n**Sources:** {}",
-        task_name(output.task),
+        output.task,
         spec.sources
             .iter()
-            .map(|source| source.source)
+            .map(|source| source.source.as_str())
             .collect::<Vec<_>>()
             .join(", ")
     ));
     lines.push(String::new());
 
-    let mut headers = vec!["Rank".to_string(), "Model".to_string()];
+    let mut headers = vec!["Rank".to_string(), "Model".to_string(), "Score".to_string()];
     headers.extend(spec.sources.iter().map(|source| source.label.to_string()));
     lines.push(format!("| {} |", headers.join(" | ")));
     lines.push(format!(
@@ -587,32 +1668,144 @@ fn render_markdown(spec: &TaskSpec, output: &RecommendOutput) -> String {
     ));
 
     for row in &output.rows {
-        let mut values = vec![row.rank.to_string(), row.model.clone()];
-        for source_spec in spec.sources {
+        let mut values = vec![
+            row.rank.to_string(),
+            model_cell(row).replace('*', "\\*"),
+            composite_cell(row),
+        ];
+        for source_spec in &spec.sources {
             let cell = row
                 .metrics
-                .get(source_spec.source)
+                .get(&source_spec.source)
                 .and_then(|value| *value)
-                .map(|value| format_metric(source_spec.metric, value))
+                .map(|value| format_metric(&source_spec.metric, value))
                 .unwrap_or_else(|| "—".to_string());
             values.push(cell);
         }
         lines.push(format!("| {} |", values.join(" | ")));
     }
+    if output.rows.iter().any(|row| row.mixed_effort) {
+        lines.push(String::new());
+        lines.push(MIXED_EFFORT_NOTE.replace('*', "\\*"));
+    }
+
+    if !output.eliminated.is_empty() {
+        lines.push(String::new());
+        lines.push("**Eliminated by constraints:**".to_string());
+        lines.push(String::new());
+        for model in &output.eliminated {
+            lines.push(format!(
+                "- #{} `{}`: {}",
+                model.rank,
+                model.model,
+                model.reasons.join("; ")
+            ));
+        }
+    }
 
     lines.join("\n")
 }
 
-fn padded_row(values: &[String], widths: &[usize]) -> String {
-    values
+/// Rank × task grid; models in several tasks' top are marked `+` and listed
+/// with their placements underneath.
+fn render_matrix(output: &AllTasksOutput, markdown: bool) -> String {
+    let highlighted: HashSet<&str> = output
+        .highlights
         .iter()
-        .enumerate()
-        .map(|(index, value)| format!("{value:width$}", width = widths[index]))
-        .collect::<Vec<_>>()
-        .join("  ")
+        .map(|highlight| highlight.model.as_str())
+        .collect();
+    let depth = output
+        .tasks
+        .iter()
+        .map(|task| task.rows.len())
+        .max()
+        .unwrap_or(0);
+
+    let mut headers = vec!["Rank".to_string()];
+    headers.extend(output.tasks.iter().map(|task| task.task.clone()));
+    let rows: Vec<Vec<String>> = (0..depth)
+        .map(|index| {
+            let mut cells = vec![(index + 1).to_string()];
+            cells.extend(output.tasks.iter().map(|task| match task.rows.get(index) {
+                Some(row) if highlighted.contains(row.model.as_str()) => {
+                    format!("{} +", row.model)
+                }
+                Some(row) => row.model.clone(),
+                None => "—".to_string(),
+            }));
+            cells
+        })
+        .collect();
+
+    let mut lines = Vec::new();
+    if markdown {
+        lines.push(format!("| {} |", headers.join(" | ")));
+        lines.push(format!("| {} |", vec!["---"; headers.len()].join(" | ")));
+        for row in &rows {
+            lines.push(format!("| {} |", row.join(" | ")));
+        }
+    } else {
+        let mut widths: Vec<usize> = headers
+            .iter()
+            .map(|header| header.chars().count())
+            .collect();
+        for row in &rows {
+            for (index, cell) in row.iter().enumerate() {
+                widths[index] = widths[index].max(cell.chars().count());
+            }
+        }
+        lines.push(padded_row(&headers, &widths));
+        lines.push(
+            widths
+                .iter()
+                .map(|width| "-".repeat(*width))
+                .collect::<Vec<_>>()
+                .join("  "),
+        );
+        for row in &rows {
+            lines.push(padded_row(row, &widths));
+        }
+    }
+
+    if !output.highlights.is_empty() {
+        lines.push(String::new());
+        lines.push(format!(
+            "{} In the top {} of several tasks:",
+            if markdown { "**+**" } else { "+" },
+            output.top
+        ));
+        let bullet = if markdown { "- " } else { "  " };
+        for highlight in &output.highlights {
+            let placements = highlight
+                .placements
+                .iter()
+                .map(|placement| format!("{} #{}", placement.task, placement.rank))
+                .collect::<Vec<_>>()
+                .join(", ");
+            lines.push(format!("{bullet}{}: {placements}", highlight.model));
+        }
+    }
+    lines.join("\n")
 }
 
-fn format_metric(metric_name: &str, metric: RecommendMetricValue) -> String {
+const MIXED_EFFORT_NOTE: &str =
+    "* values read from rows of different effort; see `reasons` or --variant";
+
+/// The model name, starred when its cells come from rows of different effort.
+fn model_cell(row: &RankedModel) -> String {
+    if row.mixed_effort {
+        format!("{} *", row.model)
+    } else {
+        row.model.clone()
+    }
+}
+
+fn composite_cell(row: &RankedModel) -> String {
+    row.composite
+        .map_or_else(|| "—".to_string(), |score| format!("{score:.3}"))
+}
+
+pub fn format_metric(metric_name: &str, metric: RecommendMetricValue) -> String {
     match metric {
         RecommendMetricValue::Float(value) => match metric_name {
             "resolved_rate" | "resolve_rate" | "pass_rate_1" => format!("{value:.1}%"),
@@ -624,7 +1817,7 @@ fn format_metric(metric_name: &str, metric: RecommendMetricValue) -> String {
     }
 }
 
-fn status_label(status: &SourceStatus) -> &'static str {
+pub fn status_label(status: &SourceStatus) -> &'static str {
     match status {
         SourceStatus::Ok => "OK",
         SourceStatus::Cached => "Cached",
@@ -633,19 +1826,10 @@ fn status_label(status: &SourceStatus) -> &'static str {
     }
 }
 
-fn task_name(task: RecommendTask) -> &'static str {
-    match task {
-        RecommendTask::Coding => "coding",
-        RecommendTask::Agentic => "agentic",
-        RecommendTask::Intelligence => "intelligence",
-        RecommendTask::General => "general",
-        RecommendTask::Cost => "cost",
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::Variant;
     use std::collections::HashMap;
 
     fn make_score(model: &str, metric_name: &str, value: RecommendMetricValue) -> ModelScore {
@@ -657,11 +1841,40 @@ mod tests {
         ModelScore {
             model: model.to_string(),
             source_model_name: model.to_string(),
+            variant: Variant::default(),
+            agent: None,
             metrics: HashMap::from([(metric_name.to_string(), metric)]),
             rank: None,
         }
     }
 
+    fn builtin(name: &str) -> TaskSpec {
+        spec_for_task(&RecommendConfig::default(), name).unwrap()
+    }
+
+    fn options(top: usize, scoring: Scoring) -> RecommendOptions {
+        RecommendOptions {
+            top,
+            effort: EffortFilter::All,
+            latest_per_family: false,
+            cost_mix: TokenMix::from_config(&Default::default()),
+            scoring,
+            variant: VariantPolicy::Best,
+            constraints: Constraints::default(),
+        }
+    }
+
+    fn rank(
+        spec: &TaskSpec,
+        results: &[SourceResult],
+        aliases: &AliasMap,
+        top: usize,
+        scoring: Scoring,
+    ) -> Vec<RankedModel> {
+        let options = options(top, scoring);
+        rank_models(spec, results, aliases, &options, &ConstraintData::default()).rows
+    }
+
     fn make_source(source: &str, scores: Vec<ModelScore>) -> SourceResult {
         SourceResult {
             source: source.to_string(),
@@ -674,42 +1887,42 @@ mod tests {
     #[test]
     fn task_taxonomy_matches_expected_sources() {
         assert_eq!(
-            spec_for_task(RecommendTask::Coding)
+            builtin("coding")
                 .sources
                 .iter()
-                .map(|source| source.source)
+                .map(|source| source.source.as_str())
                 .collect::<Vec<_>>(),
             vec!["swebench", "terminal-bench", "aider", "swe-rebench"]
         );
         assert_eq!(
-            spec_for_task(RecommendTask::Agentic)
+            builtin("agentic")
                 .sources
                 .iter()
-                .map(|source| source.source)
+                .map(|source| source.source.as_str())
                 .collect::<Vec<_>>(),
             vec!["terminal-bench", "seal"]
         );
         assert_eq!(
-            spec_for_task(RecommendTask::Intelligence)
+            builtin("intelligence")
                 .sources
                 .iter()
-                .map(|source| source.source)
+                .map(|source| source.source.as_str())
                 .collect::<Vec<_>>(),
             vec!["artificial-analysis"]
         );
         assert_eq!(
-            spec_for_task(RecommendTask::General)
+            builtin("general")
                 .sources
                 .iter()
-                .map(|source| source.source)
+                .map(|source| source.source.as_str())
                 .collect::<Vec<_>>(),
             vec!["arena"]
         );
         assert_eq!(
-            spec_for_task(RecommendTask::Cost)
+            builtin("cost")
                 .sources
                 .iter()
-                .map(|source| source.source)
+                .map(|source| source.source.as_str())
                 .collect::<Vec<_>>(),
             vec!["openrouter"]
         );
@@ -717,8 +1930,8 @@ mod tests {
 
     #[test]
     fn ranking_prefers_primary_metric_when_partial_data_exists() {
-        let aliases = AliasMap::load(Some("/tmp/pondus-recommend-no-override.toml")).unwrap();
-        let spec = spec_for_task(RecommendTask::Coding);
+        let aliases = AliasMap::bundled();
+        let spec = &builtin("coding");
         let results = vec![
             make_source(
                 "swebench",
@@ -746,7 +1959,7 @@ mod tests {
             make_source("terminal-bench", vec![]),
         ];
 
-        let ranked = rank_models(spec, &results, &aliases, 10);
+        let ranked = rank(spec, &results, &aliases, 10, Scoring::Primary);
         assert_eq!(
             ranked
                 .iter()
@@ -768,4 +1981,477 @@ mod tests {
                 .is_some()
         );
     }
+
+    #[test]
+    fn composite_scoring_weighs_every_source() {
+        let aliases = AliasMap::bundled();
+        let mut spec = builtin("coding");
+        let results = vec![
+            make_source(
+                "swebench",
+                vec![
+                    make_score(
+                        "model-a",
+                        "resolved_rate",
+                        RecommendMetricValue::Float(80.0),
+                    ),
+                    make_score(
+                        "model-b",
+                        "resolved_rate",
+                        RecommendMetricValue::Float(78.0),
+                    ),
+                ],
+            ),
+            make_source(
+                "aider",
+                vec![
+                    make_score("model-c", "pass_rate_1", RecommendMetricValue::Float(95.0)),
+                    make_score("model-b", "pass_rate_1", RecommendMetricValue::Float(82.0)),
+                ],
+            ),
+        ];
+        let order = |spec: &TaskSpec| {
+            rank(spec, &results, &aliases, 10, Scoring::Composite)
+                .into_iter()
+                .map(|row| (row.model, row.composite.unwrap()))
+                .collect::<Vec<_>>()
+        };
+
+        // a and c each top one source and sit at the median of the other;
+        // the primary metric breaks their tie
+        assert_eq!(
+            order(&spec),
+            vec![
+                ("model-a".to_string(), 0.75),
+                ("model-c".to_string(), 0.75),
+                ("model-b".to_string(), 0.0),
+            ]
+        );
+
+        spec.sources[2].weight = 3.0;
+        let weighted = order(&spec);
+        assert_eq!(weighted[0], ("model-c".to_string(), 0.875));
+        assert_eq!(weighted[1], ("model-a".to_string(), 0.625));
+
+        let rows = rank(&spec, &results, &aliases, 1, Scoring::Composite);
+        assert_eq!(rows[0].components.get("aider"), Some(&Some(1.0)));
+        assert_eq!(rows[0].components.get("swebench"), Some(&None));
+    }
+
+    #[test]
+    fn rows_carry_rank_context_and_reasons_per_source() {
+        let aliases = AliasMap::bundled();
+        let spec = &builtin("coding");
+        let mut swebench = make_source(
+            "swebench",
+            ["model-a", "model-b", "model-c"]
+                .iter()
+                .zip([80.0, 78.0, 78.0])
+                .map(|(model, rate)| {
+                    make_score(model, "resolved_rate", RecommendMetricValue::Float(rate))
+                })
+                .collect(),
+        );
+        swebench.fetched_at = Some(Utc::now() - chrono::Duration::days(3));
+        let results = vec![swebench, make_source("aider", vec![])];
+
+        let rows = rank(spec, &results, &aliases, 3, Scoring::Primary);
+        let cell = rows[2].cells["swebench"].as_ref().unwrap();
+        assert_eq!((cell.rank, cell.total), (2, 3));
+        assert_eq!(cell.percentile, 0.25);
+        assert_eq!(cell.age_days, Some(3));
+        assert_eq!(cell.source_model_name, "model-c");
+        assert!(rows[2].cells["aider"].is_none());
+        assert_eq!(
+            rows[2].reasons,
+            vec![
+                "#3 for coding, composite score 0.250, listed by 1 of 4 sources",
+                "SWE-bench: 78.0%, rank 2 of 3, row \"model-c\" not in the alias map, data 3 days old",
+                "Terminal-Bench: not listed",
+                "Aider: not listed",
+                "SWE-rebench: not listed",
+            ]
+        );
+    }
+
+    #[test]
+    fn variant_policy_applies_to_every_task_source() {
+        let aliases = AliasMap::bundled();
+        let spec = &builtin("coding");
+        let row = |source_model_name: &str, metric: &str, value: f64| ModelScore {
+            source_model_name: source_model_name.to_string(),
+            variant: crate::variant::parse_variant(source_model_name),
+            ..make_score("model-a", metric, RecommendMetricValue::Float(value))
+        };
+        let results = vec![
+            make_source(
+                "swebench",
+                vec![
+                    row("model-a (high)", "resolved_rate", 80.0),
+                    row("model-a", "resolved_rate", 74.0),
+                    row("model-a (low)", "resolved_rate", 60.0),
+                ],
+            ),
+            make_source(
+                "aider",
+                vec![
+                    row("model-a", "pass_rate_1", 70.0),
+                    row("model-a (high)", "pass_rate_1", 75.0),
+                ],
+            ),
+        ];
+        let picked = |variant: VariantPolicy| {
+            let mut options = options(1, Scoring::Primary);
+            options.variant = variant;
+            let rows = rank_models(
+                spec,
+                &results,
+                &aliases,
+                &options,
+                &ConstraintData::default(),
+            )
+            .rows;
+            let names: Vec<String> = ["swebench", "aider"]
+                .iter()
+                .map(|source| {
+                    rows[0].cells[*source]
+                        .as_ref()
+                        .unwrap()
+                        .source_model_name
+                        .clone()
+                })
+                .collect();
+            (names, rows[0].mixed_effort)
+        };
+
+        assert_eq!(
+            picked(VariantPolicy::Best).0,
+            ["model-a (high)", "model-a (high)"]
+        );
+        assert_eq!(
+            picked(VariantPolicy::Standard),
+            (vec!["model-a".to_string(), "model-a".to_string()], false)
+        );
+        // aider has no low row, so its standard row is the cheapest and the mix is flagged
+        assert_eq!(
+            picked(VariantPolicy::Cheapest),
+            (
+                vec!["model-a (low)".to_string(), "model-a".to_string()],
+                true
+            )
+        );
+    }
+
+    #[test]
+    fn highlights_list_models_topping_several_tasks() {
+        let aliases = AliasMap::bundled();
+        let results = vec![
+            make_source(
+                "swebench",
+                vec![
+                    make_score(
+                        "model-a",
+                        "resolved_rate",
+                        RecommendMetricValue::Float(80.0),
+                    ),
+                    make_score(
+                        "model-b",
+                        "resolved_rate",
+                        RecommendMetricValue::Float(70.0),
+                    ),
+                ],
+            ),
+            make_source(
+                "arena",
+                vec![
+                    make_score("model-b", "elo_score", RecommendMetricValue::Float(1500.0)),
+                    make_score("model-c", "elo_score", RecommendMetricValue::Float(1450.0)),
+                ],
+            ),
+            make_source(
+                "artificial-analysis",
+                vec![
+                    make_score(
+                        "model-c",
+                        "intelligence_index",
+                        RecommendMetricValue::Float(70.0),
+                    ),
+                    make_score(
+                        "model-b",
+                        "intelligence_index",
+                        RecommendMetricValue::Float(60.0),
+                    ),
+                ],
+            ),
+        ];
+        let options = options(2, Scoring::Primary);
+        let tasks: Vec<TaskRanking> = ["coding", "general", "intelligence"]
+            .iter()
+            .map(|name| TaskRanking {
+                task: name.to_string(),
+                description: String::new(),
+                rows: rank_models(
+                    &builtin(name),
+                    &results,
+                    &aliases,
+                    &options,
+                    &ConstraintData::default(),
+                )
+                .rows,
+                eliminated: Vec::new(),
+            })
+            .collect();
+
+        let highlights = highlights(&tasks);
+        let summary: Vec<(&str, Vec<(&str, usize)>)> = highlights
+            .iter()
+            .map(|highlight| {
+                (
+                    highlight.model.as_str(),
+                    highlight
+                        .placements
+                        .iter()
+                        .map(|placement| (placement.task.as_str(), placement.rank))
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "model-b",
+                    vec![("coding", 2), ("general", 1), ("intelligence", 2)]
+                ),
+                ("model-c", vec![("general", 2), ("intelligence", 1)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn constraints_report_what_removed_each_higher_ranked_model() {
+        let aliases = AliasMap::bundled();
+        let spec = &builtin("coding");
+        let swebench = ["model-a", "model-b", "model-c", "model-d"]
+            .iter()
+            .zip([80.0, 78.0, 76.0, 74.0])
+            .map(|(model, rate)| {
+                make_score(model, "resolved_rate", RecommendMetricValue::Float(rate))
+            })
+            .collect();
+        let listing = |model: &str, prompt: f64, completion: f64, context: i64| ModelScore {
+            source_model_name: format!("vendor/{model}"),
+            metrics: HashMap::from([
+                ("prompt_per_1m".to_string(), MetricValue::Float(prompt)),
+                (
+                    "completion_per_1m".to_string(),
+                    MetricValue::Float(completion),
+                ),
+                ("context_length".to_string(), MetricValue::Int(context)),
+            ]),
+            ..make_score(model, "rank", RecommendMetricValue::Int(0))
+        };
+        let results = vec![make_source("swebench", swebench)];
+        let openrouter = make_source(
+            "openrouter",
+            vec![
+                listing("model-a", 10.0, 50.0, 200_000),
+                listing("model-b", 1.0, 2.0, 100_000),
+                listing("model-c", 1.0, 1.0, 400_000),
+            ],
+        );
+        let data = ConstraintData {
+            stale: Vec::new(),
+            listings: Some(openrouter_listings(&openrouter, &aliases)),
+        };
+
+        let mut options = options(1, Scoring::Primary);
+        options.constraints.max_price_per_1m = Some(5.0);
+        options.constraints.min_context = Some(128_000);
+        let selection = rank_models(spec, &results, &aliases, &options, &data);
+        assert_eq!(selection.rows.len(), 1);
+        assert_eq!(selection.rows[0].model, "model-c");
+        assert_eq!(selection.rows[0].rank, 1);
+        let eliminated: Vec<(usize, &str, &[String])> = selection
+            .eliminated
+            .iter()
+            .map(|model| (model.rank, model.model.as_str(), model.reasons.as_slice()))
+            .collect();
+        assert_eq!(
+            eliminated,
+            vec![
+                (
+                    1,
+                    "model-a",
+                    &["--max-price-per-1m 5: $20.00 blended on OpenRouter".to_string()][..]
+                ),
+                (
+                    2,
+                    "model-b",
+                    &["--min-context 128k: 100k context window".to_string()][..]
+                ),
+            ]
+        );
+
+        // With nothing left to show, every model and each of its reasons is listed
+        options.constraints.exclude = vec!["MODEL-C".to_string()];
+        options.constraints.providers = vec!["other".to_string()];
+        let selection = rank_models(spec, &results, &aliases, &options, &data);
+        assert!(selection.rows.is_empty());
+        assert_eq!(selection.eliminated.len(), 4);
+        assert_eq!(
+            selection.eliminated[2].reasons,
+            vec!["--exclude MODEL-C", "--provider other: listed under vendor"]
+        );
+        assert_eq!(
+            selection.eliminated[3].reasons,
+            vec![
+                "--max-price-per-1m 5: no OpenRouter price",
+                "--min-context 128k: no OpenRouter context length",
+                "--provider other: not listed on OpenRouter",
+            ]
+        );
+    }
+
+    #[test]
+    fn explanation_reports_the_rule_against_each_neighbour() {
+        let aliases = AliasMap::bundled();
+        let spec = &builtin("coding");
+        let swebench =
+            |model, value| make_score(model, "resolved_rate", RecommendMetricValue::Float(value));
+        let results = vec![
+            make_source(
+                "swebench",
+                vec![
+                    swebench("model-a", 80.0),
+                    swebench("model-b", 75.0),
+                    swebench("model-c", 75.0),
+                    swebench("model-d", 75.0),
+                ],
+            ),
+            make_source(
+                "aider",
+                vec![
+                    make_score("model-b", "pass_rate_1", RecommendMetricValue::Float(70.0)),
+                    make_score("model-c", "pass_rate_1", RecommendMetricValue::Float(70.0)),
+                ],
+            ),
+        ];
+        let options = options(0, Scoring::Primary);
+
+        let explained = explain_position(spec, &results, &aliases, &options, "model-b");
+        assert_eq!((explained.rank, explained.total), (Some(2), 4));
+        let above = explained.above.unwrap();
+        assert_eq!(above.model, "model-a");
+        assert_eq!(above.decided_by, TieBreak::Primary("swebench".to_string()));
+        assert_eq!(above.detail, "SWE-bench 75.0% vs 80.0%");
+        let below = explained.below.unwrap();
+        assert_eq!(below.model, "model-c");
+        assert_eq!(below.decided_by, TieBreak::Name);
+
+        let explained = explain_position(spec, &results, &aliases, &options, "model-d");
+        let above = explained.above.unwrap();
+        assert_eq!(above.decided_by, TieBreak::Coverage);
+        assert!(explained.below.is_none());
+
+        let missing = explain_position(spec, &results, &aliases, &options, "model-z");
+        assert!(missing.rank.is_none() && missing.above.is_none());
+    }
+
+    fn recommend_config(toml: &str) -> RecommendConfig {
+        toml::from_str::<Config>(toml).unwrap().recommend
+    }
+
+    #[test]
+    fn config_tasks_extend_and_override_the_built_ins() {
+        let config = recommend_config(
+            r#"
+            [recommend.tasks.data-extraction]
+            description = "Structured output from long documents."
+            sources = [
+                { source = "artificial-analysis", metric = "intelligence_index", weight = 2.0 },
+                { source = "openrouter", metric = "prompt_per_1m", sort = "asc", label = "Input $" },
+            ]
+
+            [recommend.tasks.coding]
+            sources = [{ source = "aider", metric = "pass_rate_1" }]
+            "#,
+        );
+
+        let specs = task_specs(&config).unwrap();
+        let names: Vec<&str> = specs.iter().map(|spec| spec.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "coding",
+                "agentic",
+                "intelligence",
+                "general",
+                "cost",
+                "data-extraction"
+            ]
+        );
+
+        let coding = spec_for_task(&config, "Coding").unwrap();
+        assert_eq!(coding.origin, TaskOrigin::Config);
+        assert_eq!(coding.sources.len(), 1);
+        assert_eq!(coding.sources[0].label, "Aider");
+
+        let extraction = spec_for_task(&config, "data-extraction").unwrap();
+        assert_eq!(extraction.sources[0].weight, 2.0);
+        assert_eq!(extraction.sources[0].sort, SortDirection::Desc);
+        assert_eq!(extraction.sources[1].sort, SortDirection::Asc);
+        assert_eq!(extraction.sources[1].label, "Input $");
+    }
+
+    #[test]
+    fn invalid_config_tasks_are_rejected() {
+        let unknown = recommend_config(
+            r#"[recommend.tasks.x]
+            sources = [{ source = "nope", metric = "m" }]"#,
+        );
+        let err = task_specs(&unknown).unwrap_err().to_string();
+        assert!(err.contains("unknown source 'nope'"), "{err}");
+
+        let twice = recommend_config(
+            r#"[recommend.tasks.x]
+            sources = [{ source = "aider", metric = "a" }, { source = "aider", metric = "b" }]"#,
+        );
+        assert!(task_specs(&twice).is_err());
+
+        let err = spec_for_task(&RecommendConfig::default(), "poetry")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("coding, agentic"), "{err}");
+    }
+
+    #[test]
+    fn config_task_metric_typos_are_reported() {
+        let config = recommend_config(
+            r#"[recommend.tasks.x]
+            sources = [
+                { source = "swebench", metric = "resolved_rat" },
+                { source = "aider", metric = "pass_rate_1" },
+                { source = "arena", metric = "elo_score" },
+            ]"#,
+        );
+        let spec = spec_for_task(&config, "x").unwrap();
+        let float = RecommendMetricValue::Float(70.0);
+        let results = vec![
+            make_source(
+                "swebench",
+                vec![make_score("model-a", "resolved_rate", float)],
+            ),
+            make_source("aider", vec![make_score("model-a", "pass_rate_1", float)]),
+            // Nothing fetched: left to the source's status line
+            make_source("arena", Vec::new()),
+        ];
+        let cost_mix = TokenMix::from_config(&Default::default());
+
+        let missing: Vec<&str> = missing_metrics(&spec, &results, &cost_mix)
+            .iter()
+            .map(|source_spec| source_spec.metric.as_str())
+            .collect();
+        assert_eq!(missing, vec!["resolved_rat"]);
+    }
 }
@@ -0,0 +1,333 @@
+use crate::alias::AliasMap;
+use crate::cache::Cache;
+use crate::config::Config;
+use crate::models::SourceResult;
+use crate::recommend::{
+    RankedModel, RecommendOptions, Scoring, TaskSpec, fetch_recommend_sources, select_models,
+    spec_for_task, task_specs,
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Router config flavours `export-routing` writes, chosen by `--format`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoutingFormat {
+    /// LiteLLM proxy `model_list` and `router_settings.fallbacks` (YAML)
+    Litellm,
+    /// OpenRouter request `models` arrays keyed by task (JSON)
+    OpenrouterFallbacks,
+    /// Every route with ranks and scores
+    Json,
+}
+
+impl RoutingFormat {
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "litellm" => Ok(Self::Litellm),
+            "openrouter-fallbacks" => Ok(Self::OpenrouterFallbacks),
+            "json" => Ok(Self::Json),
+            _ => anyhow::bail!(
+                "Unknown routing format: {s}. Expected: litellm, openrouter-fallbacks, json"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RouteTarget {
+    pub model: String,
+    pub openrouter_id: String,
+    /// Position in the recommend task
+    pub rank: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub composite: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskRoute {
+    pub task: String,
+    pub primary: Option<RouteTarget>,
+    pub fallbacks: Vec<RouteTarget>,
+    /// Models ranked above the last target that OpenRouter doesn't list
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unroutable: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RoutingOutput {
+    pub timestamp: DateTime<Utc>,
+    pub scoring: Scoring,
+    pub routes: Vec<TaskRoute>,
+}
+
+/// Rank each task (all of them when `tasks` is empty) and print its primary
+/// model and fallbacks in `format`. `options.top` is ignored.
+pub fn run(
+    config: &Config,
+    cache: &Cache,
+    aliases: &AliasMap,
+    tasks: &[String],
+    fallbacks: usize,
+    options: &RecommendOptions,
+    format: RoutingFormat,
+) -> Result<()> {
+    let specs: Vec<TaskSpec> = if tasks.is_empty() {
+        task_specs(&config.recommend)?
+    } else {
+        tasks
+            .iter()
+            .map(|task| spec_for_task(&config.recommend, task))
+            .collect::<Result<_>>()?
+    };
+
+    let openrouter = fetch_recommend_sources(config, cache, &["openrouter"])?;
+    let ids = openrouter_ids(&openrouter[0], aliases);
+
+    // Every ranked model, so unroutable ones can be passed over
+    let options = RecommendOptions {
+        top: usize::MAX,
+        ..options.clone()
+    };
+    let mut routes = Vec::with_capacity(specs.len());
+    for spec in &specs {
+        let (_, selection) = select_models(config, cache, aliases, spec, &options)?;
+        let route = route(&spec.name, &selection.rows, &ids, fallbacks);
+        if route.primary.is_none() {
+            eprintln!(
+                "[warn] {}: no ranked model has an OpenRouter id; left out",
+                spec.name
+            );
+        }
+        routes.push(route);
+    }
+
+    let output = RoutingOutput {
+        timestamp: Utc::now(),
+        scoring: options.scoring,
+        routes,
+    };
+    println!("{}", render(&output, format)?);
+    Ok(())
+}
+
+/// The OpenRouter id for each canonical model. A model with several ids
+/// gets its plain one over `:free` and other suffixed ids, then the shortest.
+pub fn openrouter_ids(result: &SourceResult, aliases: &AliasMap) -> HashMap<String, String> {
+    let preference = |id: &str| (id.contains(':'), id.len(), id.to_string());
+    let mut ids: HashMap<String, String> = HashMap::new();
+    for score in &result.scores {
+        let id = &score.source_model_name;
+        let model = aliases.canonical_for(score);
+        match ids.get(&model) {
+            Some(existing) if preference(existing) <= preference(id) => {}
+            _ => {
+                ids.insert(model, id.clone());
+            }
+        }
+    }
+    ids
+}
+
+/// The first `fallbacks + 1` ranked models with an OpenRouter id, best first.
+pub fn route(
+    task: &str,
+    rows: &[RankedModel],
+    ids: &HashMap<String, String>,
+    fallbacks: usize,
+) -> TaskRoute {
+    let mut targets = Vec::new();
+    let mut unroutable = Vec::new();
+    for row in rows {
+        if targets.len() > fallbacks {
+            break;
+        }
+        match ids.get(&row.model) {
+            Some(id) => targets.push(RouteTarget {
+                model: row.model.clone(),
+                openrouter_id: id.clone(),
+                rank: row.rank,
+                composite: row.composite,
+            }),
+            None => unroutable.push(row.model.clone()),
+        }
+    }
+
+    let mut targets = targets.into_iter();
+    TaskRoute {
+        task: task.to_string(),
+        primary: targets.next(),
+        fallbacks: targets.collect(),
+        unroutable,
+    }
+}
+
+pub fn render(output: &RoutingOutput, format: RoutingFormat) -> Result<String> {
+    Ok(match format {
+        RoutingFormat::Json => serde_json::to_string_pretty(output)?,
+        RoutingFormat::OpenrouterFallbacks => {
+            serde_json::to_string_pretty(&openrouter_fallbacks(output))?
+        }
+        RoutingFormat::Litellm => serde_yaml::to_string(&litellm_config(output))?,
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct OpenRouterRequest {
+    /// Tried in order until one answers
+    models: Vec<String>,
+}
+
+fn openrouter_fallbacks(output: &RoutingOutput) -> BTreeMap<String, OpenRouterRequest> {
+    output
+        .routes
+        .iter()
+        .filter_map(|route| {
+            let primary = route.primary.as_ref()?;
+            let models = std::iter::once(primary)
+                .chain(&route.fallbacks)
+                .map(|target| target.openrouter_id.clone())
+                .collect();
+            Some((route.task.clone(), OpenRouterRequest { models }))
+        })
+        .collect()
+}
+
+#[derive(Debug, Serialize)]
+struct LitellmConfig {
+    model_list: Vec<LitellmModel>,
+    router_settings: LitellmRouterSettings,
+}
+
+#[derive(Debug, Serialize)]
+struct LitellmModel {
+    model_name: String,
+    litellm_params: LitellmParams,
+}
+
+#[derive(Debug, Serialize)]
+struct LitellmParams {
+    model: String,
+}
+
+#[derive(Debug, Serialize)]
+struct LitellmRouterSettings {
+    fallbacks: Vec<BTreeMap<String, Vec<String>>>,
+}
+
+/// One model group per target: `<task>` for the primary and
+/// `<task>-fallback-<n>` for the rest, with the task falling back through them.
+fn litellm_config(output: &RoutingOutput) -> LitellmConfig {
+    let mut model_list = Vec::new();
+    let mut fallbacks = Vec::new();
+    for route in &output.routes {
+        let Some(primary) = &route.primary else {
+            continue;
+        };
+        let deployment = |model_name: String, target: &RouteTarget| LitellmModel {
+            model_name,
+            litellm_params: LitellmParams {
+                model: format!("openrouter/{}", target.openrouter_id),
+            },
+        };
+        model_list.push(deployment(route.task.clone(), primary));
+
+        let mut names = Vec::with_capacity(route.fallbacks.len());
+        for (index, target) in route.fallbacks.iter().enumerate() {
+            let name = format!("{}-fallback-{}", route.task, index + 1);
+            model_list.push(deployment(name.clone(), target));
+            names.push(name);
+        }
+        if !names.is_empty() {
+            fallbacks.push(BTreeMap::from([(route.task.clone(), names)]));
+        }
+    }
+
+    LitellmConfig {
+        model_list,
+        router_settings: LitellmRouterSettings { fallbacks },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranked(rank: usize, model: &str) -> RankedModel {
+        RankedModel {
+            rank,
+            model: model.to_string(),
+            composite: None,
+            components: BTreeMap::new(),
+            metrics: BTreeMap::new(),
+            cells: BTreeMap::new(),
+            reasons: Vec::new(),
+            mixed_effort: false,
+        }
+    }
+
+    #[test]
+    fn routes_skip_models_without_an_openrouter_id() {
+        let rows = vec![
+            ranked(1, "claude-code"),
+            ranked(2, "gpt-5.2"),
+            ranked(3, "glm-5"),
+            ranked(4, "kimi-k2.5"),
+        ];
+        let ids = HashMap::from([
+            ("gpt-5.2".to_string(), "openai/gpt-5.2".to_string()),
+            ("glm-5".to_string(), "z-ai/glm-5".to_string()),
+            ("kimi-k2.5".to_string(), "moonshotai/kimi-k2.5".to_string()),
+        ]);
+        let route = route("coding", &rows, &ids, 1);
+
+        assert_eq!(
+            route.primary.as_ref().unwrap().openrouter_id,
+            "openai/gpt-5.2"
+        );
+        assert_eq!(route.fallbacks.len(), 1);
+        assert_eq!(route.fallbacks[0].model, "glm-5");
+        assert_eq!(route.unroutable, vec!["claude-code"]);
+
+        let output = RoutingOutput {
+            timestamp: Utc::now(),
+            scoring: Scoring::Composite,
+            routes: vec![route],
+        };
+        let yaml = render(&output, RoutingFormat::Litellm).unwrap();
+        assert!(yaml.contains("model_name: coding-fallback-1"), "{yaml}");
+        assert!(yaml.contains("model: openrouter/z-ai/glm-5"), "{yaml}");
+        assert!(yaml.contains("- coding-fallback-1"), "{yaml}");
+
+        let fallbacks = render(&output, RoutingFormat::OpenrouterFallbacks).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&fallbacks).unwrap();
+        assert_eq!(
+            parsed["coding"]["models"],
+            serde_json::json!(["openai/gpt-5.2", "z-ai/glm-5"])
+        );
+    }
+
+    #[test]
+    fn plain_openrouter_ids_win_over_suffixed_ones() {
+        let aliases = AliasMap::bundled();
+        let score = |id: &str| crate::models::ModelScore {
+            model: "model-a".to_string(),
+            source_model_name: id.to_string(),
+            variant: Default::default(),
+            agent: None,
+            metrics: HashMap::new(),
+            rank: None,
+        };
+        let result = SourceResult {
+            source: "openrouter".to_string(),
+            fetched_at: None,
+            status: crate::models::SourceStatus::Cached,
+            scores: vec![score("vendor/model-a:free"), score("vendor/model-a")],
+        };
+        assert_eq!(
+            openrouter_ids(&result, &aliases)["model-a"],
+            "vendor/model-a"
+        );
+    }
+}
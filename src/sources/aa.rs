@@ -1,10 +1,11 @@
 use crate::cache::Cache;
 use crate::config::Config;
 use crate::models::{MetricValue, ModelScore, SourceResult, SourceStatus, SourceTag};
+use crate::ranking::competition_ranks;
 use crate::sources::Source;
+use crate::variant::parse_variant;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use clap::ValueEnum;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::process::Command;
@@ -13,53 +14,6 @@ use std::time::Duration;
 pub struct ArtificialAnalysis;
 static TAGS: &[SourceTag] = &[SourceTag::Reasoning, SourceTag::General];
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum AaEffort {
-    Max,
-    Standard,
-    Low,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
-pub enum AaEffortFilter {
-    Max,
-    Standard,
-    Low,
-    All,
-}
-
-impl Default for AaEffortFilter {
-    fn default() -> Self {
-        Self::All
-    }
-}
-
-impl AaEffortFilter {
-    pub fn matches(self, effort: AaEffort) -> bool {
-        match self {
-            Self::All => true,
-            Self::Max => effort == AaEffort::Max,
-            Self::Standard => effort == AaEffort::Standard,
-            Self::Low => effort == AaEffort::Low,
-        }
-    }
-}
-
-pub fn classify_effort_level(model_name: &str) -> AaEffort {
-    let normalized = model_name.to_lowercase();
-
-    if normalized.contains("(max)") || normalized.contains("adaptive") {
-        AaEffort::Max
-    } else if normalized.contains("low-effort")
-        || normalized.contains("low effort")
-        || normalized.contains("(low)")
-    {
-        AaEffort::Low
-    } else {
-        AaEffort::Standard
-    }
-}
-
 impl Source for ArtificialAnalysis {
     fn name(&self) -> &str {
         "artificial-analysis"
@@ -229,19 +183,21 @@ impl ArtificialAnalysis {
             })
             .unwrap_or_default();
 
-        rows.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        rows.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let ranks = competition_ranks(rows.iter().map(|row| row.1));
 
         let scores = rows
             .into_iter()
-            .enumerate()
-            .map(|(idx, (source_model_name, score))| {
-                let rank = (idx + 1) as u32;
+            .zip(ranks)
+            .map(|((source_model_name, score), rank)| {
                 let mut metrics = HashMap::new();
                 metrics.insert("intelligence_index".into(), MetricValue::Float(score));
                 metrics.insert("rank".into(), MetricValue::Int(rank as i64));
 
                 ModelScore {
                     model: source_model_name.to_lowercase().replace([' ', '_'], "-"),
+                    variant: parse_variant(&source_model_name),
+                    agent: None,
                     source_model_name,
                     metrics,
                     rank: Some(rank),
@@ -405,43 +361,3 @@ fn extract_cell_value(line: &str) -> Option<String> {
     let end = line[start..].find('"')? + start;
     Some(line[start..end].to_string())
 }
-
-#[cfg(test)]
-mod tests {
-    use super::{AaEffort, classify_effort_level};
-
-    #[test]
-    fn classify_effort_level_detects_max_variants() {
-        assert_eq!(classify_effort_level("Claude 4.5 (Max)"), AaEffort::Max);
-        assert_eq!(
-            classify_effort_level("o3 adaptive reasoning"),
-            AaEffort::Max
-        );
-    }
-
-    #[test]
-    fn classify_effort_level_detects_low_variants() {
-        assert_eq!(
-            classify_effort_level("Gemini 2.5 Pro (non-reasoning,-low-effort)"),
-            AaEffort::Low
-        );
-        assert_eq!(
-            classify_effort_level("Gemini 2.5 Pro low-effort"),
-            AaEffort::Low
-        );
-        assert_eq!(
-            classify_effort_level("Claude Sonnet 4.6 (Non-reasoning, Low Effort)"),
-            AaEffort::Low
-        );
-        assert_eq!(classify_effort_level("Gemini 3 Pro (low)"), AaEffort::Low);
-    }
-
-    #[test]
-    fn classify_effort_level_defaults_to_standard() {
-        assert_eq!(
-            classify_effort_level("Claude 4.5 non-reasoning"),
-            AaEffort::Standard
-        );
-        assert_eq!(classify_effort_level("Claude 4.5"), AaEffort::Standard);
-    }
-}
@@ -1,7 +1,9 @@
 use crate::cache::Cache;
 use crate::config::Config;
 use crate::models::{MetricValue, ModelScore, SourceResult, SourceStatus, SourceTag};
+use crate::ranking::rank_scores;
 use crate::sources::Source;
+use crate::variant::parse_variant;
 use anyhow::{Context, Result};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
@@ -114,30 +116,15 @@ fn parse_scores(data: &serde_json::Value) -> Vec<ModelScore> {
         scores.push(ModelScore {
             model: model_name.to_lowercase().replace([' ', '_'], "-"),
             source_model_name: model_name.to_string(),
+            variant: parse_variant(model_name),
+            agent: None,
             metrics,
             rank: None,
         });
     }
 
-    // Sort by pass_rate_1 descending
-    scores.sort_by(|a, b| {
-        let a_rate = get_float(&a.metrics, "pass_rate_1");
-        let b_rate = get_float(&b.metrics, "pass_rate_1");
-        b_rate
-            .partial_cmp(&a_rate)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-
-    for (i, score) in scores.iter_mut().enumerate() {
-        score.rank = Some((i + 1) as u32);
-    }
+    // Sort by pass_rate_1 descending; equal scores share a rank
+    rank_scores(&mut scores, "pass_rate_1");
 
     scores
 }
-
-fn get_float(metrics: &HashMap<String, MetricValue>, key: &str) -> f64 {
-    match metrics.get(key) {
-        Some(MetricValue::Float(f)) => *f,
-        _ => 0.0,
-    }
-}
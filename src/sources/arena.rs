@@ -1,7 +1,9 @@
 use crate::cache::Cache;
 use crate::config::Config;
 use crate::models::{MetricValue, ModelScore, SourceResult, SourceStatus, SourceTag};
+use crate::ranking::{competition_ranks, upper_bound_ranks};
 use crate::sources::Source;
+use crate::variant::parse_variant;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
@@ -40,9 +42,10 @@ impl Arena {
     fn fetch_scrape(&self, config: &Config, cache: &Cache) -> Result<SourceResult> {
         let agent_browser = config.agent_browser_path();
 
-        if let Err(err) =
-            run_agent_browser(agent_browser, &["open", "https://lmarena.ai/leaderboard/text"])
-        {
+        if let Err(err) = run_agent_browser(
+            agent_browser,
+            &["open", "https://lmarena.ai/leaderboard/text"],
+        ) {
             return Ok(map_command_error(self.name(), "open", err));
         }
 
@@ -68,10 +71,11 @@ impl Arena {
 
         let cached_rows: Vec<serde_json::Value> = parsed
             .iter()
-            .map(|(name, elo)| {
+            .map(|(name, elo, ci)| {
                 serde_json::json!({
                     "source_model_name": name,
                     "elo_score": elo,
+                    "elo_ci": ci,
                 })
             })
             .collect();
@@ -136,7 +140,7 @@ impl Arena {
         fetched_at: Option<DateTime<Utc>>,
         status: SourceStatus,
     ) -> SourceResult {
-        let mut rows: Vec<(String, f64)> = data
+        let mut rows: Vec<(String, f64, Option<f64>)> = data
             .get("scores")
             .and_then(|v| v.as_array())
             .map(|arr| {
@@ -147,25 +151,39 @@ impl Arena {
                             .and_then(|v| v.as_str())
                             .map(ToOwned::to_owned)?;
                         let elo = entry.get("elo_score").and_then(|v| v.as_f64())?;
-                        Some((name, elo))
+                        let ci = entry.get("elo_ci").and_then(|v| v.as_f64());
+                        Some((name, elo, ci))
                     })
                     .collect::<Vec<_>>()
             })
             .unwrap_or_default();
 
-        rows.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        rows.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let ranks = competition_ranks(rows.iter().map(|row| row.1));
+        // Rows without a published CI count as exact when bounding the others
+        let bounds: Vec<(f64, f64)> = rows
+            .iter()
+            .map(|row| (row.1, row.2.unwrap_or(0.0)))
+            .collect();
+        let upper_bound_ranks = upper_bound_ranks(&bounds);
 
         let scores = rows
             .into_iter()
-            .enumerate()
-            .map(|(idx, (source_model_name, elo))| {
-                let rank = (idx + 1) as u32;
+            .zip(ranks.into_iter().zip(upper_bound_ranks))
+            .map(|((source_model_name, elo, ci), (rank, rank_ub))| {
                 let mut metrics = HashMap::new();
                 metrics.insert("elo_score".into(), MetricValue::Float(elo));
+                if let Some(ci) = ci {
+                    metrics.insert("elo_ci".into(), MetricValue::Float(ci));
+                    // Models sharing a rank_ub are statistically tied
+                    metrics.insert("rank_ub".into(), MetricValue::Int(rank_ub as i64));
+                }
                 metrics.insert("rank".into(), MetricValue::Int(rank as i64));
 
                 ModelScore {
                     model: source_model_name.to_lowercase().replace([' ', '_'], "-"),
+                    variant: parse_variant(&source_model_name),
+                    agent: None,
                     source_model_name,
                     metrics,
                     rank: Some(rank),
@@ -197,9 +215,10 @@ impl Arena {
 /// ```
 ///
 /// We extract the model name from the link inside cell 2, and ELO from cell 3
-/// (taking only the numeric prefix before the space, discarding "±…" and "Preliminary").
-fn parse_scores_from_snapshot(text: &str) -> Vec<(String, f64)> {
-    let mut results: HashMap<String, f64> = HashMap::new();
+/// (the numeric prefix), plus the 95% CI half-width from its "±…" token when
+/// present. "Preliminary" is discarded.
+fn parse_scores_from_snapshot(text: &str) -> Vec<(String, f64, Option<f64>)> {
+    let mut results: HashMap<String, (f64, Option<f64>)> = HashMap::new();
     let lines: Vec<&str> = text.lines().collect();
     let mut i = 0;
     let mut found_first_table = false;
@@ -241,7 +260,12 @@ fn parse_scores_from_snapshot(text: &str) -> Vec<(String, f64)> {
             // 5-column layout: 0=Rank, 1=RankSpread, 2=Provider+Model, 3=ELO±CI, 4=Votes
             // ELO cell value is "1503 ±8" or "1500 ±9 Preliminary" — take first token.
             if cells.len() >= 4 {
-                let elo_str = cells[3].split_whitespace().next().unwrap_or("");
+                let mut elo_tokens = cells[3].split_whitespace();
+                let elo_str = elo_tokens.next().unwrap_or("");
+                let ci = elo_tokens
+                    .next()
+                    .and_then(|t| t.strip_prefix('±'))
+                    .and_then(|t| t.parse::<f64>().ok());
                 // Prefer the link name (cleaner), fall back to stripping provider from cell text
                 let model_name = model_link_name.unwrap_or_else(|| {
                     let cell = &cells[2];
@@ -259,7 +283,7 @@ fn parse_scores_from_snapshot(text: &str) -> Vec<(String, f64)> {
                     && elo > 500.0
                     && !model_name.is_empty()
                 {
-                    results.entry(model_name).or_insert(elo);
+                    results.entry(model_name).or_insert((elo, ci));
                 }
             }
 
@@ -272,6 +296,7 @@ fn parse_scores_from_snapshot(text: &str) -> Vec<(String, f64)> {
     results
         .into_iter()
         .filter(|(name, _)| !is_image_or_video_model(name))
+        .map(|(name, (elo, ci))| (name, elo, ci))
         .collect()
 }
 
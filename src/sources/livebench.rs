@@ -1,7 +1,9 @@
 use crate::cache::Cache;
 use crate::config::Config;
 use crate::models::{MetricValue, ModelScore, SourceResult, SourceStatus, SourceTag};
+use crate::ranking::competition_ranks;
 use crate::sources::Source;
+use crate::variant::parse_variant;
 use anyhow::Result;
 use chrono::Utc;
 use std::collections::HashMap;
@@ -154,19 +156,21 @@ impl LiveBench {
             })
             .unwrap_or_default();
 
-        rows.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        rows.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let ranks = competition_ranks(rows.iter().map(|row| row.1));
 
         let scores = rows
             .into_iter()
-            .enumerate()
-            .map(|(idx, (source_model_name, score))| {
-                let rank = (idx + 1) as u32;
+            .zip(ranks)
+            .map(|((source_model_name, score), rank)| {
                 let mut metrics = HashMap::new();
                 metrics.insert("global_average".into(), MetricValue::Float(score));
                 metrics.insert("rank".into(), MetricValue::Int(rank as i64));
 
                 ModelScore {
                     model: source_model_name.to_lowercase().replace([' ', '_'], "-"),
+                    variant: parse_variant(&source_model_name),
+                    agent: None,
                     source_model_name,
                     metrics,
                     rank: Some(rank),
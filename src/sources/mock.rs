@@ -2,6 +2,7 @@ use crate::cache::Cache;
 use crate::config::Config;
 use crate::models::{MetricValue, ModelScore, SourceResult, SourceStatus, SourceTag};
 use crate::sources::Source;
+use crate::variant::parse_variant;
 use anyhow::Result;
 use chrono::Utc;
 use std::collections::HashMap;
@@ -23,6 +24,8 @@ impl Source for MockSource {
             ModelScore {
                 model: "claude-opus-4.6".into(),
                 source_model_name: "Claude Opus 4.6".into(),
+                variant: parse_variant("Claude Opus 4.6"),
+                agent: None,
                 metrics: HashMap::from([
                     ("score".into(), MetricValue::Float(92.5)),
                     ("rank".into(), MetricValue::Int(1)),
@@ -32,6 +35,8 @@ impl Source for MockSource {
             ModelScore {
                 model: "gpt-5.2".into(),
                 source_model_name: "GPT-5.2".into(),
+                variant: parse_variant("GPT-5.2"),
+                agent: None,
                 metrics: HashMap::from([
                     ("score".into(), MetricValue::Float(89.1)),
                     ("rank".into(), MetricValue::Int(2)),
@@ -41,6 +46,8 @@ impl Source for MockSource {
             ModelScore {
                 model: "gemini-3.1-pro".into(),
                 source_model_name: "Gemini 3.1 Pro".into(),
+                variant: parse_variant("Gemini 3.1 Pro"),
+                agent: None,
                 metrics: HashMap::from([
                     ("score".into(), MetricValue::Float(87.3)),
                     ("rank".into(), MetricValue::Int(3)),
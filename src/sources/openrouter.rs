@@ -2,6 +2,7 @@ use crate::cache::Cache;
 use crate::config::Config;
 use crate::models::{MetricValue, ModelScore, SourceResult, SourceStatus, SourceTag};
 use crate::sources::Source;
+use crate::variant::parse_variant;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
@@ -70,11 +71,22 @@ impl OpenRouter {
                 if prompt_per_token == 0.0 && completion_per_token == 0.0 {
                     return None;
                 }
-                Some(serde_json::json!({
+                let mut row = serde_json::json!({
                     "source_model_name": model.id,
                     "prompt_per_1m": prompt_per_token * 1_000_000.0,
                     "completion_per_1m": completion_per_token * 1_000_000.0,
-                }))
+                });
+                if let Some(cache_read_per_token) = pricing
+                    .input_cache_read
+                    .and_then(|price| price.parse::<f64>().ok())
+                {
+                    row["cache_read_per_1m"] =
+                        serde_json::json!(cache_read_per_token * 1_000_000.0);
+                }
+                if let Some(context_length) = model.context_length {
+                    row["context_length"] = serde_json::json!(context_length);
+                }
+                Some(row)
             })
             .collect();
 
@@ -111,22 +123,30 @@ impl OpenRouter {
                             .get("source_model_name")
                             .and_then(|v| v.as_str())
                             .map(ToOwned::to_owned)?;
-                        let prompt_per_1m = entry
-                            .get("prompt_per_1m")
-                            .and_then(|v| v.as_f64())?;
-                        let completion_per_1m = entry
-                            .get("completion_per_1m")
-                            .and_then(|v| v.as_f64())?;
+                        let prompt_per_1m = entry.get("prompt_per_1m").and_then(|v| v.as_f64())?;
+                        let completion_per_1m =
+                            entry.get("completion_per_1m").and_then(|v| v.as_f64())?;
 
                         let mut metrics = HashMap::new();
-                        metrics.insert(
-                            "prompt_per_1m".into(),
-                            MetricValue::Float(prompt_per_1m),
-                        );
+                        metrics.insert("prompt_per_1m".into(), MetricValue::Float(prompt_per_1m));
                         metrics.insert(
                             "completion_per_1m".into(),
                             MetricValue::Float(completion_per_1m),
                         );
+                        if let Some(cache_read_per_1m) =
+                            entry.get("cache_read_per_1m").and_then(|v| v.as_f64())
+                        {
+                            metrics.insert(
+                                "cache_read_per_1m".into(),
+                                MetricValue::Float(cache_read_per_1m),
+                            );
+                        }
+                        if let Some(context_length) =
+                            entry.get("context_length").and_then(|v| v.as_i64())
+                        {
+                            metrics
+                                .insert("context_length".into(), MetricValue::Int(context_length));
+                        }
 
                         // Normalise model ID for alias matching:
                         // "openai/gpt-5.2-pro" → "openai/gpt-5.2-pro" (keep as-is,
@@ -135,6 +155,8 @@ impl OpenRouter {
 
                         Some(ModelScore {
                             model,
+                            variant: parse_variant(&source_model_name),
+                            agent: None,
                             source_model_name,
                             metrics,
                             rank: None, // pricing has no rank ordering
@@ -162,10 +184,14 @@ struct OpenRouterResponse {
 struct OpenRouterModel {
     id: String,
     pricing: Option<OpenRouterPricing>,
+    /// Largest context window across the model's providers, in tokens
+    context_length: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
 struct OpenRouterPricing {
     prompt: Option<String>,
     completion: Option<String>,
+    /// Per-token price of prompt-cache reads, where the provider supports it
+    input_cache_read: Option<String>,
 }
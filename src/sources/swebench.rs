@@ -1,7 +1,9 @@
 use crate::cache::Cache;
 use crate::config::Config;
 use crate::models::{MetricValue, ModelScore, SourceResult, SourceStatus, SourceTag};
+use crate::ranking::rank_scores;
 use crate::sources::Source;
+use crate::variant::{parse_variant, split_scaffold};
 use anyhow::{Context, Result};
 use chrono::Utc;
 use std::collections::HashMap;
@@ -110,18 +112,8 @@ fn parse_scores(data: &serde_json::Value) -> Vec<ModelScore> {
 
     let mut deduped: Vec<ModelScore> = best_by_name.into_values().collect();
 
-    // Sort by resolved_rate descending, assign ranks
-    deduped.sort_by(|a, b| {
-        let a_rate = get_float(&a.metrics, "resolved_rate");
-        let b_rate = get_float(&b.metrics, "resolved_rate");
-        b_rate
-            .partial_cmp(&a_rate)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-
-    for (i, score) in deduped.iter_mut().enumerate() {
-        score.rank = Some((i + 1) as u32);
-    }
+    // Sort by resolved_rate descending; equal scores share a rank
+    rank_scores(&mut deduped, "resolved_rate");
 
     deduped
 }
@@ -143,9 +135,15 @@ fn extract_model_score(result: &serde_json::Value) -> Option<ModelScore> {
         metrics.insert("date".into(), MetricValue::Text(date.to_string()));
     }
 
+    // Entries are often "Scaffold + Model"; keep the scaffold separate so two
+    // harnesses running the same model don't collapse into one name.
+    let (agent, model_part) = split_scaffold(name);
+
     Some(ModelScore {
-        model: name.to_lowercase().replace([' ', '_'], "-"),
+        model: model_part.to_lowercase().replace([' ', '_'], "-"),
         source_model_name: name.to_string(),
+        variant: parse_variant(model_part),
+        agent,
         metrics,
         rank: None,
     })
@@ -1,7 +1,9 @@
 use crate::cache::Cache;
 use crate::config::Config;
 use crate::models::{MetricValue, ModelScore, SourceResult, SourceStatus, SourceTag};
+use crate::ranking::competition_ranks;
 use crate::sources::Source;
+use crate::variant::{parse_variant, split_scaffold};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
@@ -59,10 +61,11 @@ impl Source for SweRebench {
         // Cache the parsed data
         let cached_rows: Vec<serde_json::Value> = parsed
             .iter()
-            .map(|(source_model_name, score)| {
+            .map(|(source_model_name, score, sem)| {
                 serde_json::json!({
                     "source_model_name": source_model_name,
                     "score": score,
+                    "sem": sem,
                 })
             })
             .collect();
@@ -81,7 +84,7 @@ impl SweRebench {
         fetched_at: Option<DateTime<Utc>>,
         status: SourceStatus,
     ) -> SourceResult {
-        let mut rows: Vec<(String, f64)> = data
+        let mut rows: Vec<(String, f64, Option<f64>)> = data
             .get("scores")
             .and_then(|v| v.as_array())
             .map(|arr| {
@@ -92,26 +95,33 @@ impl SweRebench {
                             .and_then(|v| v.as_str())
                             .map(ToOwned::to_owned)?;
                         let score = entry.get("score").and_then(|v| v.as_f64())?;
-                        Some((source_model_name, score))
+                        let sem = entry.get("sem").and_then(|v| v.as_f64());
+                        Some((source_model_name, score, sem))
                     })
                     .collect::<Vec<_>>()
             })
             .unwrap_or_default();
 
         // Ensure sorted by score
-        rows.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        rows.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let ranks = competition_ranks(rows.iter().map(|row| row.1));
 
         let scores = rows
             .into_iter()
-            .enumerate()
-            .map(|(idx, (source_model_name, score))| {
-                let rank = (idx + 1) as u32;
+            .zip(ranks)
+            .map(|((source_model_name, score, sem), rank)| {
                 let mut metrics = HashMap::new();
                 metrics.insert("resolve_rate".into(), MetricValue::Float(score));
+                if let Some(sem) = sem {
+                    metrics.insert("resolve_rate_sem".into(), MetricValue::Float(sem));
+                }
                 metrics.insert("rank".into(), MetricValue::Int(rank as i64));
 
+                let (agent, model_part) = split_scaffold(&source_model_name);
                 ModelScore {
-                    model: normalize_model_name(&source_model_name),
+                    model: normalize_model_name(model_part),
+                    variant: parse_variant(model_part),
+                    agent,
                     source_model_name,
                     metrics,
                     rank: Some(rank),
@@ -193,8 +203,9 @@ fn map_command_error(source: &str, step: &str, err: anyhow::Error) -> SourceResu
 /// ```
 ///
 /// Columns: Rank, Model, Resolved Rate (%), SEM (±), Pass@5 (%), Cost, Tokens, Cached%.
-fn parse_scores_from_text(text: &str) -> Vec<(String, f64)> {
-    let mut results: HashMap<String, f64> = HashMap::new();
+/// Returns the resolved rate and, when the row has one, its SEM in points.
+fn parse_scores_from_text(text: &str) -> Vec<(String, f64, Option<f64>)> {
+    let mut results: HashMap<String, (f64, Option<f64>)> = HashMap::new();
     let lines: Vec<&str> = text.lines().collect();
     let mut i = 0;
 
@@ -217,7 +228,7 @@ fn parse_scores_from_text(text: &str) -> Vec<(String, f64)> {
                 j += 1;
             }
 
-            // Cells: 0=Rank, 1=Model, 2=Resolved Rate
+            // Cells: 0=Rank, 1=Model, 2=Resolved Rate, 3=SEM
             if cells.len() >= 3 {
                 let model_name = &cells[1];
                 // Parse "52.9%" → 52.9
@@ -227,7 +238,15 @@ fn parse_scores_from_text(text: &str) -> Vec<(String, f64)> {
                     && model_name.chars().any(|c| c.is_ascii_alphabetic())
                     && model_name != "Model"
                 {
-                    results.entry(model_name.clone()).or_insert(score);
+                    let sem = cells.get(3).and_then(|cell| {
+                        cell.trim()
+                            .trim_start_matches('±')
+                            .trim_end_matches('%')
+                            .trim()
+                            .parse()
+                            .ok()
+                    });
+                    results.entry(model_name.clone()).or_insert((score, sem));
                 }
             }
 
@@ -237,7 +256,10 @@ fn parse_scores_from_text(text: &str) -> Vec<(String, f64)> {
         }
     }
 
-    results.into_iter().collect()
+    results
+        .into_iter()
+        .map(|(name, (score, sem))| (name, score, sem))
+        .collect()
 }
 
 /// Extract the quoted value from a cell line like `- cell "some value" [ref=...]:`
@@ -250,3 +272,38 @@ fn extract_cell_value(line: &str) -> Option<String> {
 fn normalize_model_name(name: &str) -> String {
     name.to_lowercase().replace([' ', '_'], "-")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::parse_scores_from_text;
+
+    #[test]
+    fn parses_sem_when_the_row_has_one() {
+        let text = r#"
+- row "Rank Model Resolved Rate SEM":
+  - cell "Rank"
+  - cell "Model"
+  - cell "Resolved Rate (%)"
+  - cell "SEM (±)"
+- row "1 Claude Opus 4.6 51.7% ±1.23":
+  - cell "1"
+  - cell "Claude Opus 4.6"
+  - cell "51.7%"
+  - cell "±1.23"
+  - cell "60.0%"
+- row "2 GLM-5 40.0%":
+  - cell "2"
+  - cell "GLM-5"
+  - cell "40.0%"
+"#;
+        let mut rows = parse_scores_from_text(text);
+        rows.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            rows,
+            vec![
+                ("Claude Opus 4.6".to_string(), 51.7, Some(1.23)),
+                ("GLM-5".to_string(), 40.0, None),
+            ]
+        );
+    }
+}
@@ -1,7 +1,9 @@
 use crate::cache::Cache;
 use crate::config::Config;
 use crate::models::{MetricValue, ModelScore, SourceResult, SourceStatus, SourceTag};
+use crate::ranking::rank_scores;
 use crate::sources::Source;
+use crate::variant::parse_variant;
 use anyhow::{Context, Result};
 use chrono::Utc;
 use std::collections::HashMap;
@@ -114,8 +116,12 @@ fn extract_from_siblings(data: &serde_json::Value) -> Vec<ModelScore> {
         .map(|(agent_model, count)| {
             // Split "Agent__Model" — use only the model part for canonical name
             // so it matches other sources' normalization (e.g. "gemini-3-pro-preview")
+            // and keep the agent part as the scaffold.
             let display_name = agent_model.replace("__", " / ");
-            let model_part = agent_model.split("__").nth(1).unwrap_or(&agent_model);
+            let (agent, model_part) = match agent_model.split_once("__") {
+                Some((agent, model)) => (Some(agent.replace('-', " ")), model),
+                None => (None, agent_model.as_str()),
+            };
             let canonical = model_part.to_lowercase();
 
             let mut metrics = HashMap::new();
@@ -123,6 +129,8 @@ fn extract_from_siblings(data: &serde_json::Value) -> Vec<ModelScore> {
 
             ModelScore {
                 model: canonical,
+                variant: parse_variant(model_part),
+                agent,
                 source_model_name: display_name,
                 metrics,
                 rank: None,
@@ -130,16 +138,8 @@ fn extract_from_siblings(data: &serde_json::Value) -> Vec<ModelScore> {
         })
         .collect();
 
-    // Sort by tasks_completed descending
-    scores.sort_by(|a, b| {
-        let a_count = get_int(&a.metrics, "tasks_completed");
-        let b_count = get_int(&b.metrics, "tasks_completed");
-        b_count.cmp(&a_count)
-    });
-
-    for (i, score) in scores.iter_mut().enumerate() {
-        score.rank = Some((i + 1) as u32);
-    }
+    // Sort by tasks_completed descending; equal scores share a rank
+    rank_scores(&mut scores, "tasks_completed");
 
     scores
 }
@@ -147,10 +147,3 @@ fn extract_from_siblings(data: &serde_json::Value) -> Vec<ModelScore> {
 fn parse_scores(data: &serde_json::Value) -> Vec<ModelScore> {
     extract_from_siblings(data)
 }
-
-fn get_int(metrics: &HashMap<String, MetricValue>, key: &str) -> i64 {
-    match metrics.get(key) {
-        Some(MetricValue::Int(i)) => *i,
-        _ => 0,
-    }
-}
@@ -0,0 +1,643 @@
+use crate::alias::AliasMap;
+use crate::models::{Effort, ModelScore, SourceResult, Variant};
+use clap::ValueEnum;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum EffortFilter {
+    Max,
+    Standard,
+    Low,
+    #[default]
+    All,
+}
+
+impl EffortFilter {
+    pub fn matches(self, effort: Effort) -> bool {
+        match self {
+            Self::All => true,
+            Self::Max => effort == Effort::Max,
+            Self::Standard => effort == Effort::Standard,
+            Self::Low => effort == Effort::Low,
+        }
+    }
+}
+
+/// Which row represents a model when a source lists several variants of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum VariantPolicy {
+    /// The best-ranked row, whatever its effort
+    #[default]
+    Best,
+    /// The best-ranked standard-effort row
+    Standard,
+    /// The best-ranked max-effort row
+    Max,
+    /// The best-ranked low-effort row
+    Low,
+    /// The best-ranked row at the lowest effort listed, which spends the
+    /// fewest tokens
+    Cheapest,
+}
+
+impl VariantPolicy {
+    /// Pick a representative from `candidates`, which must be non-empty and
+    /// sorted best rank first. Falls back to the best row when no candidate has
+    /// the requested effort, so a model is never dropped for lacking a variant.
+    pub fn pick<'a>(self, candidates: &[&'a ModelScore]) -> &'a ModelScore {
+        let wanted = match self {
+            Self::Best => None,
+            Self::Standard => Some(Effort::Standard),
+            Self::Max => Some(Effort::Max),
+            Self::Low => Some(Effort::Low),
+            Self::Cheapest => candidates
+                .iter()
+                .map(|score| score.variant.effort)
+                .min_by_key(|effort| match effort {
+                    Effort::Low => 0,
+                    Effort::Standard => 1,
+                    Effort::Max => 2,
+                }),
+        };
+        wanted
+            .and_then(|effort| {
+                candidates
+                    .iter()
+                    .find(|score| score.variant.effort == effort)
+            })
+            .unwrap_or(&candidates[0])
+    }
+}
+
+/// Classify the reasoning effort a source row was run at.
+///
+/// Explicit effort words win over implicit reasoning markers, and anything
+/// labelled non-reasoning is never `Max` — AA's "(Non-reasoning, High Effort)"
+/// is its standard tier, not a thinking variant. Only words that qualify the
+/// name count (see [`qualifier_words`]), so `Qwen3 Max` and
+/// `gpt-5.1-codex-max` stay standard.
+pub fn classify_effort_level(model_name: &str) -> Effort {
+    let normalized = model_name.to_lowercase();
+    let non_reasoning = is_non_reasoning(&normalized);
+    let words = qualifier_words(&normalized);
+    let has = |word: &str| words.contains(&word);
+
+    if has("low") || has("minimal") {
+        Effort::Low
+    } else if non_reasoning || has("medium") {
+        Effort::Standard
+    } else if has("max")
+        || has("xhigh")
+        || has("high")
+        || has("adaptive")
+        || has("thinking")
+        || has("reasoning")
+    {
+        Effort::Max
+    } else {
+        Effort::Standard
+    }
+}
+
+/// Parse the qualifiers a source attaches to a model name into a [`Variant`]:
+/// effort level, thinking mode, snapshot date and token budgets such as `32k`.
+///
+/// ```text
+/// claude-sonnet-4-5-20250929-thinking-32k → max, thinking, 2025-09-29, [thinking, 32k]
+/// gpt-5.2-2025-12-11-medium               → standard, 2025-12-11, [medium]
+/// Claude Opus 4.6 (Non-reasoning, High Effort) → standard, [non-reasoning, high]
+/// ```
+pub fn parse_variant(model_name: &str) -> Variant {
+    let normalized = model_name.to_lowercase();
+    let non_reasoning = is_non_reasoning(&normalized);
+    let words = qualifier_words(&normalized);
+
+    let mut qualifiers = Vec::new();
+    if non_reasoning {
+        qualifiers.push("non-reasoning".to_string());
+    }
+    for token in tokens(&normalized) {
+        let is_qualifier = words.contains(&token) && (token != "reasoning" || !non_reasoning);
+        if (is_qualifier || is_budget(token)) && !qualifiers.iter().any(|q| q == token) {
+            qualifiers.push(token.to_string());
+        }
+    }
+
+    Variant {
+        effort: classify_effort_level(model_name),
+        thinking: !non_reasoning
+            && words
+                .iter()
+                .any(|w| matches!(*w, "thinking" | "reasoning" | "adaptive")),
+        snapshot: snapshot_date(&normalized),
+        qualifiers,
+    }
+}
+
+/// Read a bare trailing `-max` as max effort when the rest of the name is a
+/// known model, as in `claude-opus-4.6-max`. [`parse_variant`] can't tell
+/// that apart from a model named `… Max` without the alias map, so it leaves
+/// both standard; names that are models themselves (`qwen3-max`) stay so.
+pub fn qualify_max_suffix(variant: &mut Variant, model_name: &str, aliases: &AliasMap) {
+    let normalized = model_name.trim().to_lowercase();
+    let Some(stem) = normalized
+        .strip_suffix("-max")
+        .or_else(|| normalized.strip_suffix(" max"))
+    else {
+        return;
+    };
+    if variant.effort != Effort::Standard
+        || variant
+            .qualifiers
+            .iter()
+            .any(|q| q == "non-reasoning" || q == "max")
+        || aliases.is_known(&normalized)
+        || !aliases.is_known(stem)
+    {
+        return;
+    }
+    variant.effort = Effort::Max;
+    variant.qualifiers.push("max".to_string());
+}
+
+/// Keep only rows whose variant matches the requested effort, across every
+/// source, after reading `-max` suffixes against `aliases`.
+pub fn apply_effort_filter(results: &mut [SourceResult], effort: EffortFilter, aliases: &AliasMap) {
+    for score in results
+        .iter_mut()
+        .flat_map(|result| result.scores.iter_mut())
+    {
+        qualify_max_suffix(&mut score.variant, &score.source_model_name, aliases);
+    }
+    if effort == EffortFilter::All {
+        return;
+    }
+
+    for result in results.iter_mut() {
+        result
+            .scores
+            .retain(|score| effort.matches(score.variant.effort));
+    }
+}
+
+/// Agent harnesses that appear as bare leaderboard entries (no model named).
+const KNOWN_SCAFFOLDS: &[&str] = &[
+    "claude code",
+    "codex",
+    "codex cli",
+    "gemini cli",
+    "openhands",
+    "swe-agent",
+    "mini-swe-agent",
+    "terminus",
+    "terminus 2",
+    "terminus2",
+    "warp",
+    "goose",
+    "droid",
+    "junie",
+    "cursor",
+    "devin",
+];
+
+/// Split a leaderboard entry into `(scaffold, model)`.
+///
+/// ```text
+/// OpenHands + Claude Opus 4.6                      → (OpenHands, Claude Opus 4.6)
+/// OpenHands + CodeAct v2.1 (claude-3-5-sonnet-20241022) → (OpenHands + CodeAct v2.1, claude-3-5-sonnet-20241022)
+/// Claude Code                                      → (Claude Code, Claude Code)
+/// Claude Opus 4.6                                  → (None, Claude Opus 4.6)
+/// ```
+///
+/// A bare scaffold name keeps itself as the model so the row still resolves
+/// through the alias map (e.g. `claude-code`).
+pub fn split_scaffold(name: &str) -> (Option<String>, &str) {
+    let trimmed = name.trim();
+
+    if let Some((scaffold, model)) = trimmed.split_once(" + ") {
+        // "Scaffold + Config (model-id)" — the parenthetical names the model when it
+        // starts with a letter; "(2025-05-22)" style dates stay part of the name.
+        if let Some(open) = model.rfind('(')
+            && model.ends_with(')')
+        {
+            let inner = &model[open + 1..model.len() - 1];
+            if inner.starts_with(|c: char| c.is_ascii_alphabetic()) && inner.contains('-') {
+                let harness = format!("{scaffold} + {}", model[..open].trim());
+                return (Some(harness), inner);
+            }
+        }
+        return (Some(scaffold.trim().to_string()), model.trim());
+    }
+
+    if KNOWN_SCAFFOLDS.contains(&trimmed.to_lowercase().as_str()) {
+        return (Some(trimmed.to_string()), trimmed);
+    }
+
+    (None, trimmed)
+}
+
+/// Keep only rows run under `scaffold` (case-insensitive substring match).
+/// `none` keeps rows that name no scaffold at all.
+pub fn apply_scaffold_filter(results: &mut [SourceResult], scaffold: &str) {
+    let wanted = scaffold.trim().to_lowercase();
+    for result in results.iter_mut() {
+        result.scores.retain(|score| match &score.agent {
+            Some(agent) => agent.to_lowercase().contains(&wanted),
+            None => wanted == "none",
+        });
+    }
+}
+
+/// Collapse scaffold rows so each canonical model appears once per source,
+/// keeping its best-ranked harness. Surviving rows keep their original source rank.
+pub fn keep_best_harness(results: &mut [SourceResult], aliases: &AliasMap) {
+    for result in results.iter_mut() {
+        let mut best: HashMap<String, u32> = HashMap::new();
+        for score in &result.scores {
+            let rank = score.rank.unwrap_or(u32::MAX);
+            best.entry(aliases.canonical_for(score))
+                .and_modify(|current| *current = (*current).min(rank))
+                .or_insert(rank);
+        }
+        let mut seen = HashSet::new();
+        result.scores.retain(|score| {
+            let canonical = aliases.canonical_for(score);
+            score.rank.unwrap_or(u32::MAX) == best[&canonical] && seen.insert(canonical)
+        });
+    }
+}
+
+fn is_non_reasoning(normalized: &str) -> bool {
+    normalized.contains("non-reasoning")
+        || normalized.contains("non reasoning")
+        || normalized.contains("nonreasoning")
+}
+
+fn is_effort_word(token: &str) -> bool {
+    matches!(
+        token,
+        "max"
+            | "xhigh"
+            | "high"
+            | "medium"
+            | "low"
+            | "minimal"
+            | "adaptive"
+            | "thinking"
+            | "reasoning"
+    )
+}
+
+/// A token budget such as `32k`.
+fn is_budget(token: &str) -> bool {
+    token.len() > 1
+        && token.ends_with('k')
+        && token[..token.len() - 1].chars().all(|c| c.is_ascii_digit())
+}
+
+/// Effort words that qualify a model name rather than being part of it.
+///
+/// Many models carry these words in their names (`Qwen3 Max`,
+/// `gpt-5.1-codex-max`, `Phi-4-reasoning`, `Mistral Medium`), so a word only
+/// counts when it is parenthesised (`(high)`), followed by `effort`
+/// (`low-effort`), or in the trailing suffix after a snapshot date
+/// (`-2025-12-11-xhigh`). `high`, `low` and `thinking` also count as a bare
+/// trailing suffix (`gpt-5-high`, `claude-opus-4-6-thinking`), and `xhigh`,
+/// `minimal` and `adaptive` count anywhere since no model is named after them.
+fn qualifier_words(normalized: &str) -> Vec<&str> {
+    // (token, inside parentheses)
+    let mut tokens: Vec<(&str, bool)> = Vec::new();
+    let mut depth = 0usize;
+    let mut start = None;
+    for (i, c) in normalized.char_indices().chain([(normalized.len(), ' ')]) {
+        if c.is_ascii_alphanumeric() {
+            start.get_or_insert(i);
+            continue;
+        }
+        if let Some(start) = start.take() {
+            tokens.push((&normalized[start..i], depth > 0));
+        }
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    // Where the trailing run of unparenthesised effort words and budgets starts
+    let mut suffix = tokens.len();
+    while suffix > 0 {
+        let (token, parenthesised) = tokens[suffix - 1];
+        if parenthesised || !(is_effort_word(token) || is_budget(token)) {
+            break;
+        }
+        suffix -= 1;
+    }
+    let base: Vec<&str> = tokens[..suffix].iter().map(|(token, _)| *token).collect();
+    let dated = ends_with_date(&base);
+
+    tokens
+        .iter()
+        .enumerate()
+        .filter(|(i, (token, parenthesised))| {
+            is_effort_word(token)
+                && (*parenthesised
+                    || matches!(*token, "xhigh" | "minimal" | "adaptive")
+                    || tokens.get(i + 1).is_some_and(|(next, _)| *next == "effort")
+                    || (*i >= suffix && (dated || matches!(*token, "high" | "low" | "thinking"))))
+        })
+        .map(|(_, (token, _))| *token)
+        .collect()
+}
+
+/// Whether `tokens` end in a `20250929` or `2025 12 11` snapshot date.
+fn ends_with_date(tokens: &[&str]) -> bool {
+    let digits =
+        |token: &str, len: usize| token.len() == len && token.chars().all(|c| c.is_ascii_digit());
+    match tokens {
+        [.., compact] if digits(compact, 8) && compact.starts_with("20") => true,
+        [.., year, month, day] => {
+            digits(year, 4) && year.starts_with("20") && digits(month, 2) && digits(day, 2)
+        }
+        _ => false,
+    }
+}
+
+fn tokens(normalized: &str) -> Vec<&str> {
+    normalized
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Snapshot date embedded in a model name, either `20250929` or `2025-12-11`,
+/// normalised to `YYYY-MM-DD`.
+fn snapshot_date(normalized: &str) -> Option<String> {
+    let tokens = tokens(normalized);
+
+    if let Some(compact) = tokens
+        .iter()
+        .find(|t| t.len() == 8 && t.starts_with("20") && t.chars().all(|c| c.is_ascii_digit()))
+    {
+        return Some(format!(
+            "{}-{}-{}",
+            &compact[..4],
+            &compact[4..6],
+            &compact[6..]
+        ));
+    }
+
+    tokens.windows(3).find_map(|w| {
+        let is_date = w[0].len() == 4
+            && w[0].starts_with("20")
+            && w[1].len() == 2
+            && w[2].len() == 2
+            && w.iter().all(|t| t.chars().all(|c| c.is_ascii_digit()));
+        is_date.then(|| format!("{}-{}-{}", w[0], w[1], w[2]))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_effort_level_detects_max_variants() {
+        assert_eq!(classify_effort_level("Claude 4.5 (Max)"), Effort::Max);
+        assert_eq!(classify_effort_level("o3 adaptive reasoning"), Effort::Max);
+        assert_eq!(
+            classify_effort_level("claude-opus-4-6-thinking"),
+            Effort::Max
+        );
+        assert_eq!(
+            classify_effort_level("gpt-5.2-2025-12-11-xhigh"),
+            Effort::Max
+        );
+        assert_eq!(classify_effort_level("gpt-5.2 (high)"), Effort::Max);
+    }
+
+    #[test]
+    fn artificial_analysis_reasoning_rows_are_max_effort() {
+        // AA used to count only "(max)" and adaptive rows as max effort
+        assert_eq!(classify_effort_level("GPT-5 (high)"), Effort::Max);
+        assert_eq!(
+            classify_effort_level("Claude 4.5 Sonnet (Thinking)"),
+            Effort::Max
+        );
+        assert_eq!(
+            classify_effort_level("DeepSeek R1 (Reasoning)"),
+            Effort::Max
+        );
+        assert_eq!(
+            classify_effort_level("Claude 4.5 Sonnet (Non-reasoning)"),
+            Effort::Standard
+        );
+    }
+
+    #[test]
+    fn classify_effort_level_detects_low_variants() {
+        assert_eq!(
+            classify_effort_level("Gemini 2.5 Pro (non-reasoning,-low-effort)"),
+            Effort::Low
+        );
+        assert_eq!(
+            classify_effort_level("Gemini 2.5 Pro low-effort"),
+            Effort::Low
+        );
+        assert_eq!(
+            classify_effort_level("Claude Sonnet 4.6 (Non-reasoning, Low Effort)"),
+            Effort::Low
+        );
+        assert_eq!(classify_effort_level("Gemini 3 Pro (low)"), Effort::Low);
+        assert_eq!(classify_effort_level("o3-mini-2025-01-31-low"), Effort::Low);
+    }
+
+    #[test]
+    fn classify_effort_level_defaults_to_standard() {
+        assert_eq!(
+            classify_effort_level("Claude 4.5 non-reasoning"),
+            Effort::Standard
+        );
+        assert_eq!(
+            classify_effort_level("Claude Opus 4.6 (Non-reasoning, High Effort)"),
+            Effort::Standard
+        );
+        assert_eq!(classify_effort_level("Claude 4.5"), Effort::Standard);
+        assert_eq!(classify_effort_level("minimax-m2"), Effort::Standard);
+        assert_eq!(classify_effort_level("Phi-4-reasoning"), Effort::Standard);
+        assert_eq!(classify_effort_level("Mistral Medium 3"), Effort::Standard);
+        assert_eq!(
+            classify_effort_level("gpt-5.2-2025-12-11-medium"),
+            Effort::Standard
+        );
+    }
+
+    #[test]
+    fn effort_words_in_model_names_are_not_qualifiers() {
+        assert_eq!(classify_effort_level("qwen3-max"), Effort::Standard);
+        assert_eq!(classify_effort_level("Qwen3 Max"), Effort::Standard);
+        assert_eq!(classify_effort_level("gpt-5.1-codex-max"), Effort::Standard);
+        assert_eq!(parse_variant("gpt-5.1-codex-max"), Variant::default());
+
+        // The same words still qualify once set apart from the name
+        assert_eq!(classify_effort_level("Qwen3 Max (Thinking)"), Effort::Max);
+        assert_eq!(
+            classify_effort_level("gpt-5.1-codex-max (high)"),
+            Effort::Max
+        );
+        assert_eq!(
+            classify_effort_level("gpt-5.1-codex-max-2025-11-19-low"),
+            Effort::Low
+        );
+        assert_eq!(classify_effort_level("gpt-5-high"), Effort::Max);
+    }
+
+    const MAX_SUFFIX_TOML: &str = r#"
+[claude-opus-4_6]
+canonical = "claude-opus-4.6"
+
+[qwen-3]
+canonical = "qwen-3"
+aliases = ["Qwen3"]
+
+[qwen-3-max]
+canonical = "qwen-3-max"
+aliases = ["Qwen3 Max", "qwen3-max"]
+"#;
+
+    fn parse_with(name: &str, aliases: &AliasMap) -> Variant {
+        let mut variant = parse_variant(name);
+        qualify_max_suffix(&mut variant, name, aliases);
+        variant
+    }
+
+    #[test]
+    fn trailing_max_on_a_known_model_is_max_effort() {
+        let aliases = AliasMap::from_toml(MAX_SUFFIX_TOML);
+        let variant = parse_with("claude-opus-4.6-max", &aliases);
+        assert_eq!(variant.effort, Effort::Max);
+        assert_eq!(variant.qualifiers, vec!["max"]);
+        assert_eq!(
+            parse_with("Claude-Opus-4.6 Max", &aliases).effort,
+            Effort::Max
+        );
+
+        let mut results = vec![SourceResult {
+            source: "test".to_string(),
+            fetched_at: None,
+            status: crate::models::SourceStatus::Ok,
+            scores: ["claude-opus-4.6-max", "claude-opus-4.6"]
+                .into_iter()
+                .map(|name| ModelScore {
+                    model: name.to_string(),
+                    source_model_name: name.to_string(),
+                    variant: parse_variant(name),
+                    agent: None,
+                    metrics: HashMap::new(),
+                    rank: None,
+                })
+                .collect(),
+        }];
+        apply_effort_filter(&mut results, EffortFilter::Max, &aliases);
+        let kept: Vec<&str> = results[0].scores.iter().map(|s| s.model.as_str()).collect();
+        assert_eq!(kept, vec!["claude-opus-4.6-max"]);
+    }
+
+    #[test]
+    fn trailing_max_in_a_model_name_stays_standard() {
+        let aliases = AliasMap::from_toml(MAX_SUFFIX_TOML);
+        // Models in their own right, even though "Qwen3" is known too
+        assert_eq!(parse_with("Qwen3 Max", &aliases), Variant::default());
+        assert_eq!(parse_with("qwen3-max", &aliases), Variant::default());
+        // "gpt-5.1-codex" isn't a known model, so its "-max" is part of the name
+        assert_eq!(
+            parse_with("gpt-5.1-codex-max", &aliases),
+            Variant::default()
+        );
+    }
+
+    #[test]
+    fn keep_best_harness_groups_alias_spellings() {
+        let aliases = AliasMap::from_toml(MAX_SUFFIX_TOML);
+        let row = |agent: &str, model: &str, rank: u32| ModelScore {
+            model: model.to_string(),
+            source_model_name: format!("{agent} + {model}"),
+            variant: parse_variant(model),
+            agent: Some(agent.to_string()),
+            metrics: HashMap::new(),
+            rank: Some(rank),
+        };
+        let mut results = vec![SourceResult {
+            source: "test".to_string(),
+            fetched_at: None,
+            status: crate::models::SourceStatus::Ok,
+            scores: vec![
+                row("Agent A", "claude-opus-4.6", 1),
+                row("Agent B", "qwen3", 2),
+                row("Agent C", "Claude-Opus-4.6", 3),
+                row("Agent D", "Qwen3", 4),
+            ],
+        }];
+        keep_best_harness(&mut results, &aliases);
+        let kept: Vec<(&str, Option<u32>)> = results[0]
+            .scores
+            .iter()
+            .map(|s| (s.agent.as_deref().unwrap(), s.rank))
+            .collect();
+        assert_eq!(kept, vec![("Agent A", Some(1)), ("Agent B", Some(2))]);
+    }
+
+    #[test]
+    fn parse_variant_extracts_snapshot_and_budget() {
+        let variant = parse_variant("claude-sonnet-4-5-20250929-thinking-32k");
+        assert_eq!(variant.effort, Effort::Max);
+        assert!(variant.thinking);
+        assert_eq!(variant.snapshot.as_deref(), Some("2025-09-29"));
+        assert_eq!(variant.qualifiers, vec!["thinking", "32k"]);
+    }
+
+    #[test]
+    fn parse_variant_reads_dashed_dates() {
+        let variant = parse_variant("gpt-5.2-2025-12-11-medium");
+        assert_eq!(variant.snapshot.as_deref(), Some("2025-12-11"));
+        assert!(!variant.thinking);
+    }
+
+    #[test]
+    fn parse_variant_plain_name_is_standard() {
+        assert_eq!(parse_variant("GLM-5"), Variant::default());
+    }
+
+    #[test]
+    fn split_scaffold_plus_separator() {
+        assert_eq!(
+            split_scaffold("OpenHands + Claude Opus 4.6"),
+            (Some("OpenHands".to_string()), "Claude Opus 4.6")
+        );
+        // A trailing date stays with the model name
+        assert_eq!(
+            split_scaffold("Tools + Claude 4 Opus (2025-05-22)"),
+            (Some("Tools".to_string()), "Claude 4 Opus (2025-05-22)")
+        );
+    }
+
+    #[test]
+    fn split_scaffold_parenthetical_model_id() {
+        assert_eq!(
+            split_scaffold("OpenHands + CodeAct v2.1 (claude-3-5-sonnet-20241022)"),
+            (
+                Some("OpenHands + CodeAct v2.1".to_string()),
+                "claude-3-5-sonnet-20241022"
+            )
+        );
+    }
+
+    #[test]
+    fn split_scaffold_bare_names() {
+        assert_eq!(
+            split_scaffold("Claude Code"),
+            (Some("Claude Code".to_string()), "Claude Code")
+        );
+        assert_eq!(split_scaffold("Claude Opus 4.6"), (None, "Claude Opus 4.6"));
+    }
+}
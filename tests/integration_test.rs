@@ -64,6 +64,19 @@ fn check_known_model_finds_results() {
     );
 }
 
+#[test]
+fn rank_latest_per_family_exits_zero() {
+    let out = pondus()
+        .args(["rank", "--latest-per-family"])
+        .output()
+        .expect("failed to run");
+    assert!(
+        out.status.success(),
+        "pondus rank --latest-per-family failed: {:?}",
+        out
+    );
+}
+
 #[test]
 fn check_unknown_model_exits_zero_with_warn() {
     // A typo should not crash — it exits 0 and emits a stderr warning
@@ -103,3 +116,150 @@ fn rank_invalid_tag_exits_nonzero() {
         "invalid tag should exit non-zero"
     );
 }
+
+#[test]
+fn compare_reports_a_summary() {
+    let out = pondus()
+        .args(["--format", "json", "compare", "gpt-5.2", "claude-opus-4.6"])
+        .output()
+        .expect("failed to run");
+    assert!(out.status.success(), "pondus compare failed: {:?}", out);
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("invalid JSON");
+    let pair = &json["pairs"][0];
+    let summary = &pair["summary"];
+    let sources = pair["sources"].as_array().expect("no per-source verdicts");
+
+    // Every source listing either model is either judged or counted as unshared
+    let count = |key: &str| summary[key].as_u64().expect("summary count") as usize;
+    assert_eq!(
+        count("wins") + count("losses") + count("ties") + count("unshared"),
+        sources.len()
+    );
+    for source in sources {
+        let shared = !source["first"].is_null() && !source["second"].is_null();
+        let Some(verdict) = source["verdict"].as_str() else {
+            assert!(!shared, "shared source without a verdict: {source}");
+            continue;
+        };
+        assert!(shared, "verdict for an unshared source: {source}");
+        let delta = source["delta"]
+            .as_f64()
+            .expect("judged source without a delta");
+        let within_noise = source["noise"]
+            .as_f64()
+            .is_some_and(|noise| delta.abs() <= noise);
+        let expected = if delta == 0.0 || within_noise {
+            "tie"
+        } else if delta > 0.0 {
+            "win"
+        } else {
+            "loss"
+        };
+        assert_eq!(verdict, expected, "wrong verdict for {source}");
+    }
+}
+
+#[test]
+fn correlate_exits_zero() {
+    let out = pondus()
+        .args(["correlate"])
+        .output()
+        .expect("failed to run");
+    assert!(out.status.success(), "pondus correlate failed: {:?}", out);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("\"spearman\""),
+        "expected a spearman matrix"
+    );
+}
+
+#[test]
+fn pareto_rejects_malformed_metric() {
+    let out = pondus()
+        .args(["pareto", "--cost", "openrouter"])
+        .output()
+        .expect("failed to run");
+    assert!(
+        !out.status.success(),
+        "a metric without a source should exit non-zero"
+    );
+}
+
+#[test]
+fn cost_rejects_bad_token_count() {
+    let out = pondus()
+        .args(["cost", "--input-tokens", "lots"])
+        .output()
+        .expect("failed to run");
+    assert!(
+        !out.status.success(),
+        "an unparseable token count should exit non-zero"
+    );
+}
+
+#[test]
+fn outliers_exits_zero() {
+    let out = pondus()
+        .args(["outliers", "--threshold", "0.3"])
+        .output()
+        .expect("failed to run");
+    assert!(out.status.success(), "pondus outliers failed: {:?}", out);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("\"outliers\""), "expected an outliers list");
+}
+
+#[test]
+fn explain_unknown_model_fails() {
+    let out = pondus()
+        .args(["explain", "no-such-model-xyz"])
+        .output()
+        .expect("failed to run");
+    assert!(
+        !out.status.success(),
+        "explain should fail for unknown model"
+    );
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("not found in any source"), "{stderr}");
+}
+
+#[test]
+fn advise_without_roster_fails() {
+    let out = pondus().arg("advise").output().expect("failed to run");
+    assert!(!out.status.success(), "advise needs a roster or models");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("No models to advise on"), "{stderr}");
+}
+
+#[test]
+fn advise_exit_code_separates_errors_from_no_upgrade() {
+    // A model no source lists can't have an upgrade
+    let out = pondus()
+        .args(["advise", "no-such-model-xyz", "--exit-code"])
+        .output()
+        .expect("failed to run");
+    assert_eq!(out.status.code(), Some(0), "{:?}", out);
+
+    // Errors exit with 1, never the upgrade status 2
+    let out = pondus()
+        .args([
+            "advise",
+            "no-such-model-xyz",
+            "--task",
+            "nope",
+            "--exit-code",
+        ])
+        .output()
+        .expect("failed to run");
+    assert_eq!(out.status.code(), Some(1), "{:?}", out);
+}
+
+#[test]
+fn export_routing_rejects_display_formats() {
+    let out = pondus()
+        .args(["export-routing", "coding", "--format", "table"])
+        .output()
+        .expect("failed to run");
+    assert!(!out.status.success(), "table is not a routing format");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("Unknown routing format"), "{stderr}");
+}